@@ -0,0 +1,208 @@
+//! An adversarial game like Absurdle: the answer is not picked in advance.
+//!
+//! After each guess, the host shows the feedback that keeps the most words alive.
+
+use crate::{
+    colored_print,
+    feedback::{pattern, tiles, Pattern, Tile},
+    filter::Filter,
+    play::{print_tiles, read_guess},
+    words::WordList,
+};
+use anyhow::{bail, Result};
+use crossterm::style::Color;
+use std::collections::{HashMap, HashSet};
+
+/// The maximum number of guesses searched for a guaranteed win.
+const MAX_SEARCH_DEPTH: usize = 10;
+
+/// Plays the host: the user guesses until only the guessed word is left.
+pub fn host(words: &WordList, word_length: usize, fold_accents: bool) -> Result<()> {
+    let answers = words.frequent_answers()?;
+    let valid: HashSet<&str> = words.guesses.iter().map(|w| w.0.as_str()).collect();
+    let mut filter = Filter::new(word_length);
+    println!(
+        "Guess the word of {} characters. The answer changes with every guess, \
+         to keep as many words as possible alive.",
+        word_length
+    );
+    let mut guesses = 0;
+    loop {
+        let candidates: Vec<&str> = answers
+            .iter()
+            .copied()
+            .filter(|word| filter.matches(word))
+            .collect();
+        println!("{} words left", candidates.len());
+        let prompt = format!("Guess {}: ", guesses + 1);
+        let guess = read_guess(&prompt, &valid, word_length, fold_accents);
+        guesses += 1;
+        let (pattern, _) = respond(&guess, &candidates);
+        let tiles = tiles(pattern, word_length);
+        print_tiles(&guess, &tiles);
+        filter.apply_feedback(&guess, &tiles);
+        if pattern == self::pattern(&guess, &guess) {
+            colored_print(
+                Color::Green,
+                &format!("You found the word with {} guesses!\n", guesses),
+            );
+            return Ok(());
+        }
+    }
+}
+
+/// Chooses the feedback for a guess which keeps the most candidates alive.
+///
+/// Returns the pattern and the number of candidates giving that pattern. If several patterns
+/// keep the same number of candidates, the one revealing the least is chosen: fewer green
+/// tiles first, then fewer yellow tiles.
+pub fn respond(guess: &str, candidates: &[&str]) -> (Pattern, usize) {
+    let mut buckets: HashMap<Pattern, usize> = HashMap::new();
+    for answer in candidates {
+        *buckets.entry(pattern(guess, answer)).or_default() += 1;
+    }
+    let word_length = guess.chars().count();
+    buckets
+        .into_iter()
+        .max_by_key(|&(pattern, size)| {
+            let tiles = tiles(pattern, word_length);
+            let greens = tiles.iter().filter(|&&t| t == Tile::Green).count();
+            let yellows = tiles.iter().filter(|&&t| t == Tile::Yellow).count();
+            (size, std::cmp::Reverse((greens, yellows, pattern)))
+        })
+        .unwrap_or((0, 0))
+}
+
+/// Searches the fewest guesses which are guaranteed to beat the host and prints them.
+///
+/// As the host always responds the same way to a guess, a sequence of guesses either wins or
+/// not. At each step, only the `width` guesses leaving the fewest words are searched.
+pub fn solve(words: &WordList, word_length: usize, width: usize) -> Result<()> {
+    let answers = words.frequent_answers()?;
+    let guesses: Vec<&str> = words.guesses.iter().map(|w| w.0.as_str()).collect();
+    for depth in 1..=MAX_SEARCH_DEPTH {
+        println!("Searching a win with up to {} guesses...", depth);
+        let Some(path) = search(&guesses, &answers, depth, width) else {
+            continue;
+        };
+        println!("Guaranteed win with {} guesses:", path.len());
+        let mut candidates = answers;
+        for guess in path {
+            let (pattern, left) = respond(guess, &candidates);
+            print_tiles(guess, &tiles(pattern, word_length));
+            candidates.retain(|answer| self::pattern(guess, answer) == pattern);
+            if left > 1 {
+                println!("{} words left", left);
+            }
+        }
+        return Ok(());
+    }
+    bail!(
+        "found no win with up to {} guesses, try a larger --width",
+        MAX_SEARCH_DEPTH
+    );
+}
+
+// Searches guesses to leave a single word within `depth` guesses, the last one being that word.
+fn search<'a>(
+    guesses: &[&'a str],
+    candidates: &[&'a str],
+    depth: usize,
+    width: usize,
+) -> Option<Vec<&'a str>> {
+    if let [answer] = candidates {
+        return Some(vec![answer]);
+    }
+    // with two or more words left, at least one more guess is needed to narrow them down
+    if depth < 2 {
+        return None;
+    }
+    let is_candidate: HashSet<&str> = candidates.iter().copied().collect();
+    let mut options: Vec<(usize, bool, &str, Pattern)> = guesses
+        .iter()
+        .map(|&guess| {
+            let (pattern, left) = respond(guess, candidates);
+            (left, !is_candidate.contains(guess), guess, pattern)
+        })
+        .filter(|&(left, ..)| left < candidates.len())
+        .collect();
+    // fewest words left first, prefer guesses which could be the answer
+    options.sort_by_key(|&(left, not_candidate, ..)| (left, not_candidate));
+    for (_, _, guess, pattern) in options.into_iter().take(width) {
+        let left: Vec<&str> = candidates
+            .iter()
+            .copied()
+            .filter(|answer| self::pattern(guess, answer) == pattern)
+            .collect();
+        if let Some(mut path) = search(guesses, &left, depth - 1, width) {
+            path.insert(0, guess);
+            return Some(path);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 10] = [
+        "crane", "slate", "those", "there", "geese", "speed", "abide", "eerie", "hotel", "level",
+    ];
+
+    #[test]
+    fn respond_keeps_the_largest_group() {
+        for guess in WORDS {
+            let (pattern, left) = respond(guess, &WORDS);
+            let largest = WORDS
+                .iter()
+                .map(|answer| {
+                    let p = self::pattern(guess, answer);
+                    WORDS
+                        .iter()
+                        .filter(|a| self::pattern(guess, a) == p)
+                        .count()
+                })
+                .max()
+                .unwrap();
+            assert_eq!(left, largest, "'{}'", guess);
+            assert_eq!(
+                WORDS
+                    .iter()
+                    .filter(|answer| self::pattern(guess, answer) == pattern)
+                    .count(),
+                left
+            );
+        }
+    }
+
+    #[test]
+    fn respond_reveals_the_least_on_ties() {
+        // both words are left alone, 'those' without any green tile
+        let (pattern, left) = respond("speed", &["speed", "those"]);
+        assert_eq!(left, 1);
+        assert_eq!(pattern, self::pattern("speed", "those"));
+    }
+
+    #[test]
+    fn feedback_filter_keeps_the_words_of_the_response() {
+        for first in WORDS {
+            for second in WORDS {
+                let mut candidates = WORDS.to_vec();
+                let mut filter = Filter::new(5);
+                for guess in [first, second] {
+                    let (pattern, left) = respond(guess, &candidates);
+                    filter.apply_feedback(guess, &tiles(pattern, 5));
+                    candidates.retain(|answer| self::pattern(guess, answer) == pattern);
+                    assert_eq!(candidates.len(), left);
+                    let kept: Vec<&str> = WORDS
+                        .iter()
+                        .copied()
+                        .filter(|word| filter.matches(word))
+                        .collect();
+                    assert_eq!(kept, candidates, "after '{}' and '{}'", first, second);
+                }
+            }
+        }
+    }
+}
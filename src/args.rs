@@ -0,0 +1,244 @@
+//! Command line arguments.
+
+use crate::{
+    daily::{self, Day},
+    rank::Strategy,
+    tree::Format,
+};
+use anyhow::{bail, Context, Result};
+use std::{ops::RangeInclusive, path::PathBuf};
+
+pub const WORD_LENGTHS: RangeInclusive<usize> = 4..=11;
+pub const BOARDS: RangeInclusive<usize> = 1..=16;
+
+pub const USAGE: &str = "Usage: wordle [command] [options]
+
+Commands:
+  (none)                 solve a puzzle interactively
+  solve <rows>           print the matches and best guesses for rows like crane:bygbb, exit with
+                         status 0 for one match, 2 for more matches and 3 for none
+  play                   play a game with a hidden answer
+  absurdle               play against a host which avoids giving away the answer
+  simulate               play the suggestions against every frequent answer and report the results
+  tournament             simulate several strategies and compare the results
+  tree                   write the guesses of a strategy for every answer as a decision tree
+  bench                  measure how fast the word list is filtered
+
+Options:
+  --answers <file>       words which can be the answer (default: words.txt)
+  --guesses <file>       additional words which can be guessed, but are never the answer
+  --past <file>          answers of past puzzles, hidden from the matches
+  --length <n>           number of characters of the words, 4 to 11 (default: 5)
+  --fold-accents         treat accented characters like their base character, e.g. 'é' like 'e'
+  --boards <n>           number of boards, e.g. 2 for dordle or 4 for quordle (default: 1)
+  --session <file>       file the puzzle is saved to after every change, to resume it on the
+                         next start (default: wordle-session.txt)
+  --patterns <file>      cache of the colors of every guess for every answer, for faster ranking,
+                         computed when missing or when the word list changed
+  -h, --help             print this help
+
+Options for play:
+  --daily                today's puzzle, the same for everyone using the same word list
+  --date <yyyy-mm-dd>    the daily puzzle of another day
+  --puzzle-number <n>    the daily puzzle with that number, 0 is on 2021-06-19
+  --salt <text>          a different sequence of daily puzzles for each salt
+  --window <n>           number of days a daily answer is not repeated (default: 365)
+
+Options for absurdle:
+  --solve                search the fewest guesses which are guaranteed to win
+  --width <n>            number of guesses searched at each step (default: 10)
+
+Options for solve:
+  --top <n>              number of matches and guesses printed (default: 10)
+
+Options for solve, simulate, tournament and tree:
+  --strategy <name>      file-order, entropy, minimax or frequency (default: file-order,
+                         tournament: all of them)
+  --hard                 only guess words matching all hints so far
+
+Options for simulate, tournament and tree:
+  --opener <words>       comma separated guesses played first, e.g. crane,split,
+                         a tournament plays each strategy with each opener given
+  --targets <file>       answers to play against instead of the frequent answers
+
+Options for tree:
+  --format <format>      json or dot for Graphviz (default: json)
+  --output <file>        file to write the tree to (default: print it)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Interactive,
+    Solve,
+    Play,
+    Absurdle,
+    Simulate,
+    Tournament,
+    Tree,
+    Bench,
+}
+
+pub struct Args {
+    pub command: Command,
+    pub answers: PathBuf,
+    pub guesses: Option<PathBuf>,
+    pub past: Option<PathBuf>,
+    pub word_length: usize,
+    pub fold_accents: bool,
+    pub boards: usize,
+    pub patterns: Option<PathBuf>,
+    pub session: PathBuf,
+    /// The number of the daily puzzle to play.
+    pub puzzle_number: Option<Day>,
+    pub salt: String,
+    pub window: usize,
+    /// Search a win against the absurdle host instead of playing.
+    pub solve: bool,
+    pub width: usize,
+    pub strategy: Option<Strategy>,
+    pub hard_mode: bool,
+    /// Sets of guesses played first.
+    pub openers: Vec<Vec<String>>,
+    pub targets: Option<PathBuf>,
+    /// The rows given to the solve command, e.g. `crane:bygbb`.
+    pub rows: Vec<String>,
+    pub top: usize,
+    pub format: Format,
+    pub output: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    /// Parses the arguments, without the name of the executable.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args> {
+        let mut result = Args {
+            command: Command::Interactive,
+            answers: PathBuf::from("words.txt"),
+            guesses: None,
+            past: None,
+            word_length: 5,
+            fold_accents: false,
+            boards: 1,
+            patterns: None,
+            session: PathBuf::from("wordle-session.txt"),
+            puzzle_number: None,
+            salt: String::new(),
+            window: 365,
+            solve: false,
+            width: 10,
+            strategy: None,
+            hard_mode: false,
+            openers: vec![],
+            targets: None,
+            rows: vec![],
+            top: 10,
+            format: Format::Json,
+            output: None,
+            help: false,
+        };
+        let mut args = args.into_iter().peekable();
+        if let Some(command) = args.next_if(|arg| !arg.starts_with('-')) {
+            result.command = match command.as_str() {
+                "solve" => Command::Solve,
+                "play" => Command::Play,
+                "absurdle" => Command::Absurdle,
+                "simulate" => Command::Simulate,
+                "tournament" => Command::Tournament,
+                "tree" => Command::Tree,
+                "bench" => Command::Bench,
+                _ => bail!("unknown command '{}'\n\n{}", command, USAGE),
+            };
+        }
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--answers" => result.answers = value(&mut args, &arg)?.into(),
+                "--guesses" => result.guesses = Some(value(&mut args, &arg)?.into()),
+                "--past" => result.past = Some(value(&mut args, &arg)?.into()),
+                "--length" => result.word_length = number(&mut args, &arg, WORD_LENGTHS)?,
+                "--boards" => result.boards = number(&mut args, &arg, BOARDS)?,
+                "--fold-accents" => result.fold_accents = true,
+                "--patterns" => result.patterns = Some(value(&mut args, &arg)?.into()),
+                "--session" => result.session = value(&mut args, &arg)?.into(),
+                "--daily" => result.puzzle_number = Some(daily::today() - daily::FIRST_DAY),
+                "--date" => {
+                    let date = daily::parse_date(&value(&mut args, &arg)?)?;
+                    result.puzzle_number = Some(date - daily::FIRST_DAY);
+                }
+                "--puzzle-number" => {
+                    result.puzzle_number = Some(number(&mut args, &arg, 0..=100_000)? as Day)
+                }
+                "--salt" => result.salt = value(&mut args, &arg)?,
+                "--window" => result.window = number(&mut args, &arg, 0..=100_000)?,
+                "--solve" => result.solve = true,
+                "--width" => result.width = number(&mut args, &arg, 1..=1000)?,
+                "--strategy" => result.strategy = Some(Strategy::parse(&value(&mut args, &arg)?)?),
+                "--hard" => result.hard_mode = true,
+                "--opener" => {
+                    result.openers.push(
+                        value(&mut args, &arg)?
+                            .split(',')
+                            .map(|word| word.trim().to_string())
+                            .filter(|word| !word.is_empty())
+                            .collect(),
+                    );
+                }
+                "--targets" => result.targets = Some(value(&mut args, &arg)?.into()),
+                "--top" => result.top = number(&mut args, &arg, 1..=100_000)?,
+                "--format" => result.format = Format::parse(&value(&mut args, &arg)?)?,
+                "--output" => result.output = Some(value(&mut args, &arg)?.into()),
+                "-h" | "--help" => result.help = true,
+                _ if result.command == Command::Solve && !arg.starts_with('-') => {
+                    result.rows.push(arg)
+                }
+                _ => bail!("unknown argument '{}'\n\n{}", arg, USAGE),
+            }
+        }
+        Ok(result)
+    }
+}
+
+// The value following an option.
+fn value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String> {
+    args.next()
+        .with_context(|| format!("missing value for {}", option))
+}
+
+// The number following an option, which must be in the given range.
+fn number(
+    args: &mut impl Iterator<Item = String>,
+    option: &str,
+    range: RangeInclusive<usize>,
+) -> Result<usize> {
+    let value = value(args, option)?;
+    match value.parse() {
+        Ok(n) if range.contains(&n) => Ok(n),
+        _ => bail!(
+            "invalid value '{}' for {}, must be {} to {}",
+            value,
+            option,
+            range.start(),
+            range.end()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn solve_rows() {
+        let args = parse(&["solve", "crane:bygbb", "--boards", "2", "spilt:bbbgg:gbbbb"]).unwrap();
+        assert!(args.command == Command::Solve);
+        assert_eq!(args.boards, 2);
+        assert_eq!(args.rows, ["crane:bygbb", "spilt:bbbgg:gbbbb"]);
+        assert!(parse(&["solve"]).unwrap().rows.is_empty());
+        // rows are only taken by the solve command, and options keep their value
+        assert!(parse(&["play", "crane:bygbb"]).is_err());
+        assert!(parse(&["crane:bygbb"]).is_err());
+        assert!(parse(&["solve", "--top", "crane:bygbb"]).is_err());
+    }
+}
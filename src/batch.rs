@@ -0,0 +1,137 @@
+//! Solving without interaction, for scripts: the rows are given on the command line.
+
+use crate::{
+    args::Args,
+    feedback,
+    filter::Filter,
+    print_too_many_to_rank,
+    puzzle::Puzzle,
+    rank::Strategy,
+    solver::{matching_answers, rank_matches, Ranking, MAX_MATCHES_FOR_RANKING},
+    words::{normalize, WordList},
+};
+use anyhow::{Context, Result};
+
+/// Exit status if every board has exactly one match left.
+const ONE_MATCH: i32 = 0;
+/// Exit status if some board has more than one match left.
+const MANY_MATCHES: i32 = 2;
+/// Exit status if some board has no match left.
+const NO_MATCHES: i32 = 3;
+
+/// Applies the rows of the arguments, prints the matches and the best guesses and returns the
+/// exit status.
+///
+/// A row is the guessed word and its colors separated by a colon, e.g. `crane:bygbb`, with the
+/// colors of each board when playing on several boards, e.g. `crane:bygbb:gggbb`.
+pub fn solve(words: &WordList, args: &Args) -> Result<i32> {
+    let word_length = args.word_length;
+    let args_strategy = args.strategy.unwrap_or(Strategy::FileOrder);
+    let mut puzzle = Puzzle::new(args.boards, word_length);
+    for row in &args.rows {
+        feedback::parse_row(&row.replace(':', " "), word_length)
+            .and_then(|(guess, tiles)| {
+                let guess = normalize(&guess, args.fold_accents);
+                puzzle.apply_feedback(&guess, &tiles)
+            })
+            .with_context(|| format!("invalid row '{}'", row))?;
+    }
+    // past answers are hidden, like in the interactive mode
+    let boards: Vec<Vec<usize>> = puzzle
+        .boards
+        .iter()
+        .map(|filter| {
+            let mut matches = matching_answers(words, filter);
+            matches.retain(|&i| !words.past_answers.contains(&words.answers[i].0));
+            matches
+        })
+        .collect();
+    for (i, matches) in boards.iter().enumerate() {
+        if args.boards > 1 {
+            println!("Matches on board {} ({}):", i + 1, matches.len());
+        } else {
+            println!("Matches ({}):", matches.len());
+        }
+        for &m in matches.iter().take(args.top) {
+            println!("- {}", words.answers[m].0);
+        }
+    }
+
+    // a board with a single match is not solved until that word is guessed
+    let unsolved = puzzle.unsolved();
+    if !unsolved.is_empty() && boards.iter().all(|matches| !matches.is_empty()) {
+        let unsolved_boards: Vec<(&Filter, &[usize])> = unsolved
+            .iter()
+            .map(|&i| (&puzzle.boards[i], boards[i].as_slice()))
+            .collect();
+        let Ranking {
+            strategy,
+            guesses,
+            suggestions,
+            ..
+        } = rank_matches(
+            words,
+            &unsolved_boards,
+            args_strategy,
+            args.hard_mode,
+            MAX_MATCHES_FOR_RANKING,
+        );
+        if strategy != args_strategy {
+            print_too_many_to_rank(args_strategy);
+        }
+        println!("Best guesses, ranked by {}:", strategy.name());
+        for suggestion in suggestions.iter().take(args.top) {
+            let guess = &words.guesses[guesses[suggestion.index]].0;
+            match strategy.format_score(suggestion.score) {
+                Some(score) => println!("- {} ({})", guess, score),
+                None => println!("- {}", guess),
+            }
+        }
+    }
+
+    Ok(if boards.iter().any(|matches| matches.is_empty()) {
+        NO_MATCHES
+    } else if boards.iter().all(|matches| matches.len() == 1) {
+        ONE_MATCH
+    } else {
+        MANY_MATCHES
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(rows: &[&str]) -> i32 {
+        let words = WordList::of(&["hills", "pills", "kills"], &["fills"], &["pzkfx"]);
+        let args = ["solve", "--strategy", "entropy"].iter().chain(rows);
+        let args = Args::parse(args.map(|arg| arg.to_string())).unwrap();
+        solve(&words, &args).unwrap()
+    }
+
+    #[test]
+    fn exit_status() {
+        assert_eq!(status(&[]), MANY_MATCHES);
+        assert_eq!(status(&["hills:bgggg"]), MANY_MATCHES);
+        // a single match counts, even if it was not guessed yet
+        assert_eq!(status(&["pzkfx:bbybb"]), ONE_MATCH);
+        assert_eq!(status(&["hills:ggggg"]), ONE_MATCH);
+        assert_eq!(status(&["hills:bbbbb"]), NO_MATCHES);
+
+        assert_eq!(
+            status(&["--boards", "2", "hills:ggggg:bgggg"]),
+            MANY_MATCHES
+        );
+        assert_eq!(status(&["--boards", "2", "pzkfx:bbybb:gbbbb"]), ONE_MATCH);
+        assert_eq!(status(&["--boards", "2", "hills:bgggg:bbbbb"]), NO_MATCHES);
+        // after a board is solved, the colors are only given for the other one
+        let rows = ["--boards", "2", "hills:ggggg:bgggg", "pzkfx:bbybb"];
+        assert_eq!(status(&rows), ONE_MATCH);
+        let rows = ["--boards", "2", "hills:ggggg:bgggg", "pills:bbbbb"];
+        assert_eq!(status(&rows), NO_MATCHES);
+
+        let words = WordList::of(&["hills"], &[], &[]);
+        let args = Args::parse(["solve", "hills:bgg"].map(String::from)).unwrap();
+        assert!(solve(&words, &args).is_err());
+    }
+}
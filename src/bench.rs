@@ -0,0 +1,90 @@
+//! Measuring how fast the word list is filtered.
+
+use crate::{
+    feedback::{pattern, tiles},
+    filter::Filter,
+    words::WordList,
+};
+use anyhow::Result;
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// The number of answers the filters of the benchmark are derived from.
+const SAMPLE_ANSWERS: usize = 200;
+/// How often all filters are matched against all words.
+const ROUNDS: usize = 10;
+
+/// Filters all guesses with typical filters, by matching each word against the filter and by
+/// using the word index, and prints how long both take.
+pub fn bench(words: &WordList, word_length: usize) -> Result<()> {
+    let filters = sample_filters(words, word_length);
+    println!(
+        "Filtering {} words with {} filters, {} times",
+        words.guesses.len(),
+        filters.len(),
+        ROUNDS
+    );
+    let plain = measure(|| {
+        filters
+            .iter()
+            .map(|filter| {
+                words
+                    .guesses
+                    .iter()
+                    .filter(|w| filter.matches(&w.0))
+                    .count()
+            })
+            .sum()
+    });
+    let indexed = measure(|| {
+        filters
+            .iter()
+            .map(|filter| {
+                let query = words.index.query(filter);
+                (0..words.guesses.len())
+                    .filter(|&i| query.matches(i))
+                    .count()
+            })
+            .sum()
+    });
+    let words_matched = (words.guesses.len() * filters.len() * ROUNDS) as f64;
+    for (name, duration) in [("Filter::matches", plain), ("word index", indexed)] {
+        println!(
+            "- {:<16} {:>8.1} ms, {:>6.1} ns per word",
+            name,
+            duration.as_secs_f64() * 1000.0,
+            duration.as_secs_f64() * 1e9 / words_matched
+        );
+    }
+    println!(
+        "The word index is {:.1} times as fast",
+        plain.as_secs_f64() / indexed.as_secs_f64()
+    );
+    Ok(())
+}
+
+// The empty filter, and the filters after guessing two start words for some answers.
+fn sample_filters(words: &WordList, word_length: usize) -> Vec<Filter> {
+    let openers = words.start_words(2);
+    let step = (words.answers.len() / SAMPLE_ANSWERS).max(1);
+    let mut filters = vec![Filter::new(word_length)];
+    for answer in words.answers.iter().step_by(step).take(SAMPLE_ANSWERS) {
+        let mut filter = Filter::new(word_length);
+        for opener in &openers {
+            filter.apply_feedback(opener, &tiles(pattern(opener, &answer.0), word_length));
+            filters.push(filter.clone());
+        }
+    }
+    filters
+}
+
+// The time needed for all rounds.
+fn measure(mut count_matches: impl FnMut() -> usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(count_matches());
+    }
+    start.elapsed()
+}
@@ -0,0 +1,200 @@
+//! A daily puzzle, the same for everyone using the same word list.
+//!
+//! The answer of each day is derived from the date and an optional salt, so no server is needed.
+
+use crate::hash::fnv1a;
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::{HashSet, VecDeque},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A day, as the number of days since 1970-01-01.
+pub type Day = i64;
+
+/// The day of puzzle number 0, the same as for the original wordle: 2021-06-19.
+pub const FIRST_DAY: Day = 18797;
+
+pub fn today() -> Day {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    (seconds / 86400) as Day
+}
+
+/// Parses a date like `2024-03-01`.
+pub fn parse_date(date: &str) -> Result<Day> {
+    let parse = || -> Option<Day> {
+        let mut parts = date.split('-').map(|p| p.parse::<i64>().ok());
+        let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        let days = days_from_civil(year, month, day);
+        // reject days past the end of the month, like 2024-02-30
+        (civil_from_days(days) == (year, month, day)).then_some(days)
+    };
+    parse().with_context(|| format!("invalid date '{}', expected e.g. 2024-03-01", date))
+}
+
+pub fn format_date(day: Day) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The answer of a puzzle.
+///
+/// Each day, an answer is picked from the words based on the day and the salt. An answer is
+/// never repeated within `window` days, so the answers of all days up to the puzzle are computed.
+pub fn answer<'a>(
+    words: &[&'a str],
+    puzzle_number: Day,
+    salt: &str,
+    window: usize,
+) -> Result<&'a str> {
+    if words.is_empty() {
+        bail!("there are no words to pick the answer from");
+    }
+    if puzzle_number < 0 {
+        bail!("there are no puzzles before {}", format_date(FIRST_DAY));
+    }
+    // with less words than days in the window, repeating answers can't be avoided
+    let window = window.min(words.len() - 1);
+    let mut recent: VecDeque<usize> = VecDeque::with_capacity(window + 1);
+    let mut used: HashSet<usize> = HashSet::new();
+    let mut answer = 0;
+    for day in 0..=puzzle_number {
+        let mut attempt = 0;
+        answer = loop {
+            let i = (hash(salt, day, attempt) % words.len() as u64) as usize;
+            if !used.contains(&i) {
+                break i;
+            }
+            attempt += 1;
+        };
+        if window > 0 {
+            recent.push_back(answer);
+            used.insert(answer);
+            if recent.len() > window {
+                let expired = recent.pop_front().unwrap();
+                used.remove(&expired);
+            }
+        }
+    }
+    Ok(words[answer])
+}
+
+// A hash of the day which is the same for everyone.
+fn hash(salt: &str, day: Day, attempt: u64) -> u64 {
+    let mut hash = fnv1a(
+        salt.bytes()
+            .chain(day.to_le_bytes())
+            .chain(attempt.to_le_bytes()),
+    );
+    // FNV alone distributes similar inputs poorly over small ranges
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash
+}
+
+// Howard Hinnant's algorithms to convert between days and dates.
+fn days_from_civil(year: i64, month: i64, day: i64) -> Day {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: Day) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("w{:03}", i)).collect()
+    }
+
+    #[test]
+    fn same_puzzle_and_salt_give_same_answer() {
+        let words = words(100);
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        for puzzle_number in [0, 1, 50, 400] {
+            assert_eq!(
+                answer(&words, puzzle_number, "team", 30).unwrap(),
+                answer(&words, puzzle_number, "team", 30).unwrap()
+            );
+        }
+        let sequence = |salt: &str| -> Vec<&str> {
+            (0..20)
+                .map(|day| answer(&words, day, salt, 30).unwrap())
+                .collect()
+        };
+        assert_ne!(sequence(""), sequence("team"));
+    }
+
+    #[test]
+    fn no_answer_repeats_within_window() {
+        let words = words(50);
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        let window = 30;
+        let answers: Vec<&str> = (0..200)
+            .map(|day| answer(&words, day, "", window).unwrap())
+            .collect();
+        for (day, answer) in answers.iter().enumerate() {
+            let earlier = &answers[day.saturating_sub(window)..day];
+            assert!(
+                !earlier.contains(answer),
+                "{} repeated on day {}",
+                answer,
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn window_larger_than_word_list() {
+        let words = ["aaaaa", "bbbbb", "ccccc"];
+        let answers: Vec<&str> = (0..9)
+            .map(|day| answer(&words, day, "", 365).unwrap())
+            .collect();
+        // every word is picked once before any repeats
+        for day in 0..answers.len() - 2 {
+            let mut three = answers[day..day + 3].to_vec();
+            three.sort();
+            assert_eq!(three, words);
+        }
+        assert!(answer(&[], 0, "", 365).is_err());
+        assert!(answer(&words, -1, "", 365).is_err());
+    }
+
+    #[test]
+    fn dates_and_days() {
+        assert_eq!(parse_date("2021-06-19").unwrap(), FIRST_DAY);
+        assert_eq!(format_date(FIRST_DAY + 1000), "2024-03-15");
+        assert_eq!(parse_date(&format_date(20000)).unwrap(), 20000);
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("2024-02-31").is_err());
+        assert!(parse_date("2023-02-29").is_err());
+        assert_eq!(
+            parse_date("2024-02-29").unwrap() + 1,
+            parse_date("2024-03-01").unwrap()
+        );
+    }
+}
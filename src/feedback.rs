@@ -0,0 +1,195 @@
+//! Wordle feedback: the colored tiles shown for a guessed word.
+
+use anyhow::{bail, Result};
+
+/// The color of a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    /// Character does not occur (any more) in the word.
+    Gray,
+    /// Character occurs in the word, but not at this position.
+    Yellow,
+    /// Character is at the right position.
+    Green,
+}
+
+/// The tiles of a guess, encoded as a base 3 number with the first tile as least significant digit.
+pub type Pattern = u32;
+
+/// The maximum length of a word a pattern can be computed for.
+const MAX_WORD_LENGTH: usize = 16;
+
+impl Tile {
+    fn from_digit(digit: u32) -> Tile {
+        match digit {
+            2 => Tile::Green,
+            1 => Tile::Yellow,
+            _ => Tile::Gray,
+        }
+    }
+
+    /// The character used for the tile when typing a row, see `parse_row`.
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Green => 'g',
+            Tile::Yellow => 'y',
+            Tile::Gray => 'b',
+        }
+    }
+
+    fn from_char(ch: char) -> Option<Tile> {
+        match ch {
+            'g' => Some(Tile::Green),
+            'y' => Some(Tile::Yellow),
+            'b' => Some(Tile::Gray),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a row of feedback like `crane bygbb` into the guessed word and its tiles.
+///
+/// Tiles are given as `g` (green), `y` (yellow) or `b` (black / gray). When playing on
+/// several boards, the tiles of each board follow the word, e.g. `crane bygbb gggbb`.
+pub fn parse_row(row: &str, word_length: usize) -> Result<(String, Vec<Vec<Tile>>)> {
+    let parts: Vec<&str> = row.split_whitespace().collect();
+    let [word, boards @ ..] = &parts[..] else {
+        bail!("expected a word and its colors, e.g. 'crane bygbb'");
+    };
+    if boards.is_empty() {
+        bail!("expected a word and its colors, e.g. 'crane bygbb'");
+    }
+    let word = word.to_lowercase();
+    if word.chars().count() != word_length || !word.chars().all(|c| c.is_alphabetic()) {
+        bail!("'{}' is not a word of {} characters", word, word_length);
+    }
+    let boards = boards
+        .iter()
+        .map(|colors| {
+            let tiles = colors
+                .to_lowercase()
+                .chars()
+                .map(Tile::from_char)
+                .collect::<Option<Vec<Tile>>>();
+            match tiles {
+                Some(tiles) if tiles.len() == word_length => Ok(tiles),
+                _ => bail!(
+                    "colors must be {} of 'g' (green), 'y' (yellow) or 'b' (black)",
+                    word_length
+                ),
+            }
+        })
+        .collect::<Result<Vec<Vec<Tile>>>>()?;
+    Ok((word, boards))
+}
+
+/// Computes the tiles wordle shows for `guess` if the solution is `answer`.
+pub fn pattern(guess: &str, answer: &str) -> Pattern {
+    let mut guess_chars = ['\0'; MAX_WORD_LENGTH];
+    let mut answer_chars = ['\0'; MAX_WORD_LENGTH];
+    let mut len = 0;
+    for ((g, a), (gc, ac)) in guess_chars
+        .iter_mut()
+        .zip(answer_chars.iter_mut())
+        .zip(guess.chars().zip(answer.chars()))
+    {
+        *g = gc;
+        *a = ac;
+        len += 1;
+    }
+    let mut digits = [0; MAX_WORD_LENGTH];
+    // an answer character can only be matched once, greens take precedence
+    let mut used = [false; MAX_WORD_LENGTH];
+    for i in 0..len {
+        if guess_chars[i] == answer_chars[i] {
+            digits[i] = 2;
+            used[i] = true;
+        }
+    }
+    for i in 0..len {
+        if digits[i] == 2 {
+            continue;
+        }
+        if let Some(j) = (0..len).find(|&j| !used[j] && answer_chars[j] == guess_chars[i]) {
+            digits[i] = 1;
+            used[j] = true;
+        }
+    }
+    digits[..len].iter().rev().fold(0, |code, &d| code * 3 + d)
+}
+
+/// Decodes a pattern into its tiles.
+pub fn tiles(mut pattern: Pattern, word_length: usize) -> Vec<Tile> {
+    let mut tiles = Vec::with_capacity(word_length);
+    for _ in 0..word_length {
+        tiles.push(Tile::from_digit(pattern % 3));
+        pattern /= 3;
+    }
+    tiles
+}
+
+/// Formats a pattern like the colors of a row, e.g. `bygbb`.
+pub fn format(pattern: Pattern, word_length: usize) -> String {
+    tiles(pattern, word_length)
+        .into_iter()
+        .map(Tile::to_char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pattern_of_duplicate_characters() {
+        // the second 'e' is black, as the answer has only one
+        assert_eq!(format(pattern("speed", "abide"), 5), "bbyby");
+        // the green 'e' takes the only 'e' of the answer
+        assert_eq!(format(pattern("geese", "those"), 5), "bbbgg");
+        // two of the three 'e' are found, one green and one yellow
+        assert_eq!(format(pattern("eerie", "there"), 5), "ybybg");
+        assert_eq!(format(pattern("crane", "crane"), 5), "ggggg");
+    }
+
+    #[test]
+    fn tiles_and_format_reverse_pattern() {
+        let pattern = pattern("speed", "abide");
+        assert_eq!(
+            tiles(pattern, 5),
+            [
+                Tile::Gray,
+                Tile::Gray,
+                Tile::Yellow,
+                Tile::Gray,
+                Tile::Yellow
+            ]
+        );
+        let (_, boards) = parse_row(&format!("speed {}", format(pattern, 5)), 5).unwrap();
+        assert_eq!(boards, [tiles(pattern, 5)]);
+    }
+
+    #[test]
+    fn parse_row_of_several_boards() {
+        let (word, boards) = parse_row("  Crane BYGBB gggbb ", 5).unwrap();
+        assert_eq!(word, "crane");
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0][1], Tile::Yellow);
+        assert_eq!(boards[1][..3], [Tile::Green; 3]);
+    }
+
+    #[test]
+    fn parse_row_errors() {
+        for row in [
+            "",
+            "crane",
+            "cranes bygbb",
+            "cr4ne bygbb",
+            "crane bygb",
+            "crane bygbbb",
+            "crane bxgbb",
+            "crane bygbb gg",
+        ] {
+            assert!(parse_row(row, 5).is_err(), "'{}' was accepted", row);
+        }
+    }
+}
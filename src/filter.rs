@@ -0,0 +1,441 @@
+//! The filter words are matched against.
+
+use crate::feedback::Tile;
+use std::{collections::BTreeMap, fmt};
+
+/*
+Different filter types:
+- occurence, char must occur a minimum and / or a maximum number of times
+  - 'must not occur' is a maximum of 0
+  - a black duplicate of a green or yellow character defines the exact count
+- positional, must be x or must not be x,y,z
+  - if must be x, existing 'must not be' filter can discarded
+  */
+#[derive(Debug, Clone)]
+pub enum PositionalFilter {
+    MustBe(char),
+    MustNotBe(Vec<char>),
+}
+
+/// How often a character must occur in the word.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LetterCount {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+/// A single constraint of a filter, as listed by `Filter::print`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// In the position, the character must be the given one.
+    MustBe(usize, char),
+    /// In the position, the character must not be the given one.
+    MustNotBe(usize, char),
+    /// How often the character must occur.
+    Count(char, LetterCount),
+}
+
+impl Constraint {
+    /// The same constraint for another character.
+    pub fn with_char(self, ch: char) -> Constraint {
+        match self {
+            Constraint::MustBe(pos, _) => Constraint::MustBe(pos, ch),
+            Constraint::MustNotBe(pos, _) => Constraint::MustNotBe(pos, ch),
+            Constraint::Count(_, count) => Constraint::Count(ch, count),
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Constraint::MustBe(pos, ch) => write!(f, "char {} must be {}", pos + 1, ch),
+            Constraint::MustNotBe(pos, ch) => write!(f, "char {} must not be {}", pos + 1, ch),
+            Constraint::Count(ch, count) => match (count.min, count.max) {
+                (_, Some(0)) => write!(f, "word must not contain '{}'", ch),
+                (1, None) => write!(f, "word must contain '{}'", ch),
+                (min, None) => write!(f, "word must contain at least {} x '{}'", min, ch),
+                (min, Some(max)) if min == max => {
+                    write!(f, "word must contain exactly {} x '{}'", max, ch)
+                }
+                (0, Some(max)) => write!(f, "word must contain at most {} x '{}'", max, ch),
+                (min, Some(max)) => {
+                    write!(f, "word must contain {} to {} x '{}'", min, max, ch)
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub positional: Vec<Option<PositionalFilter>>,
+    pub counts: BTreeMap<char, LetterCount>,
+}
+
+impl Filter {
+    pub fn new(word_length: usize) -> Filter {
+        Filter {
+            positional: vec![None; word_length],
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Prints the constraints, numbered so they can be removed one by one.
+    pub fn print(&self) {
+        let constraints = self.constraints();
+        if !constraints.is_empty() {
+            println!("Filter:");
+        }
+        for (i, constraint) in constraints.iter().enumerate() {
+            println!("{:>2}. {}", i + 1, constraint);
+        }
+    }
+
+    /// The constraints of the filter, in the order they are printed.
+    pub fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![];
+        for (i, p) in self.positional.iter().enumerate() {
+            match p {
+                Some(PositionalFilter::MustBe(ch)) => constraints.push(Constraint::MustBe(i, *ch)),
+                Some(PositionalFilter::MustNotBe(chars)) => {
+                    constraints.extend(chars.iter().map(|&ch| Constraint::MustNotBe(i, ch)));
+                }
+                None => {}
+            }
+        }
+        constraints.extend(
+            self.counts
+                .iter()
+                .filter(|(_, count)| count.min > 0 || count.max.is_some())
+                .map(|(&ch, &count)| Constraint::Count(ch, count)),
+        );
+        constraints
+    }
+
+    /// Adds a single constraint as it is, unlike the other methods which also derive
+    /// constraints from it. Used to restore the constraints listed by `constraints`.
+    pub fn insert(&mut self, constraint: Constraint) {
+        match constraint {
+            Constraint::MustBe(pos, ch) => {
+                self.positional[pos] = Some(PositionalFilter::MustBe(ch));
+            }
+            Constraint::MustNotBe(pos, ch) => self.add_must_not_be(pos, ch),
+            Constraint::Count(ch, count) => {
+                self.counts.insert(ch, count);
+            }
+        }
+    }
+
+    /// Removes a single constraint, keeping all others.
+    pub fn remove(&mut self, constraint: &Constraint) {
+        match *constraint {
+            Constraint::MustBe(pos, _) => self.positional[pos] = None,
+            Constraint::MustNotBe(pos, ch) => {
+                if let Some(PositionalFilter::MustNotBe(chars)) = &mut self.positional[pos] {
+                    chars.retain(|&c| c != ch);
+                    if chars.is_empty() {
+                        self.positional[pos] = None;
+                    }
+                }
+            }
+            Constraint::Count(ch, _) => {
+                self.counts.remove(&ch);
+            }
+        }
+    }
+
+    /// Replaces the character of a single constraint, keeping all others. A count is combined
+    /// with the count already known for the new character, so both still hold.
+    pub fn replace_char(&mut self, constraint: &Constraint, ch: char) {
+        self.remove(constraint);
+        match constraint.with_char(ch) {
+            Constraint::Count(ch, count) => {
+                let known = self.counts.entry(ch).or_default();
+                known.min = known.min.max(count.min);
+                known.max = match (known.max, count.max) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            constraint => self.insert(constraint),
+        }
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        for (i, c) in word.chars().enumerate() {
+            match self.positional[i] {
+                Some(PositionalFilter::MustBe(ch)) if c != ch => return false,
+                Some(PositionalFilter::MustNotBe(ref chars)) if chars.contains(&c) => return false,
+                _ => {}
+            }
+        }
+        self.counts.iter().all(|(&ch, count)| {
+            let n = word.chars().filter(|&c| c == ch).count();
+            n >= count.min && count.max.is_none_or(|max| n <= max)
+        })
+    }
+
+    /// Every word matching this filter also matches the other filter, i.e. this filter only
+    /// adds constraints to the other one.
+    ///
+    /// Only checks the constraints one by one, so it may miss that a filter is implied.
+    pub fn implies(&self, other: &Filter) -> bool {
+        let positional = self
+            .positional
+            .iter()
+            .zip(&other.positional)
+            .all(|p| match p {
+                (_, None) => true,
+                (Some(PositionalFilter::MustBe(a)), Some(PositionalFilter::MustBe(b))) => a == b,
+                (Some(PositionalFilter::MustBe(a)), Some(PositionalFilter::MustNotBe(chars))) => {
+                    !chars.contains(a)
+                }
+                (Some(PositionalFilter::MustNotBe(a)), Some(PositionalFilter::MustNotBe(b))) => {
+                    b.iter().all(|ch| a.contains(ch))
+                }
+                _ => false,
+            });
+        positional
+            && other.counts.iter().all(|(ch, other)| {
+                let count = self.counts.get(ch).copied().unwrap_or_default();
+                count.min >= other.min
+                    && other
+                        .max
+                        .is_none_or(|max| count.max.is_some_and(|m| m <= max))
+            })
+    }
+
+    /// All characters are known.
+    pub fn is_solved(&self) -> bool {
+        self.positional
+            .iter()
+            .all(|p| matches!(p, Some(PositionalFilter::MustBe(_))))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positional.iter().all(|p| p.is_none()) && self.counts.is_empty()
+    }
+
+    /// In position `pos`, the character must be `ch`.
+    pub fn set_must_be(&mut self, pos: usize, ch: char) {
+        // Any possibly existing positional filter can be discarded.
+        self.positional[pos] = Some(PositionalFilter::MustBe(ch));
+        // Widen a 'must not occur' maximum, so the new known position stays allowed.
+        let known = self.known_positions(ch);
+        if let Some(count) = self.counts.get_mut(&ch) {
+            if count.max.is_some_and(|max| max < known) {
+                count.max = Some(known);
+            }
+        }
+    }
+
+    /// In position `pos`, the character must not be `ch`.
+    pub fn add_must_not_be(&mut self, pos: usize, ch: char) {
+        match self.positional[pos] {
+            None | Some(PositionalFilter::MustBe(_)) => {
+                self.positional[pos] = Some(PositionalFilter::MustNotBe(vec![ch]));
+            }
+            Some(PositionalFilter::MustNotBe(ref mut vec)) => {
+                if !vec.contains(&ch) {
+                    vec.push(ch);
+                    vec.sort();
+                }
+            }
+        }
+    }
+
+    /// The character must occur once more than required so far.
+    pub fn add_must_occur(&mut self, ch: char) {
+        self.counts.entry(ch).or_default().min += 1;
+    }
+
+    /// The character must occur at least `min` times.
+    pub fn set_min_count(&mut self, ch: char, min: usize) {
+        let count = self.counts.entry(ch).or_default();
+        count.min = count.min.max(min);
+    }
+
+    /// The character must not occur besides the occurrences already known.
+    ///
+    /// Like the black tile in wordle, this limits the character to the positions where
+    /// it must be and to the number of times it must occur, whichever is more.
+    pub fn add_must_not_occur(&mut self, ch: char) {
+        let known = self.known_positions(ch);
+        let count = self.counts.entry(ch).or_default();
+        count.min = count.min.max(known);
+        count.max = Some(count.min);
+    }
+
+    /// Derive all filters from a guessed word and the tiles wordle showed for it.
+    pub fn apply_feedback(&mut self, guess: &str, tiles: &[Tile]) {
+        let chars: Vec<char> = guess.chars().collect();
+        for (i, (&ch, tile)) in chars.iter().zip(tiles).enumerate() {
+            match tile {
+                Tile::Green => self.set_must_be(i, ch),
+                Tile::Yellow => self.add_must_not_be_unless_known(i, ch),
+                Tile::Gray => {
+                    // A black duplicate of a green or yellow character is not in this position.
+                    let is_duplicate = chars
+                        .iter()
+                        .zip(tiles)
+                        .any(|(&c, &t)| c == ch && t != Tile::Gray);
+                    if is_duplicate {
+                        self.add_must_not_be_unless_known(i, ch);
+                    }
+                }
+            }
+        }
+        for (i, &ch) in chars.iter().enumerate() {
+            if chars[..i].contains(&ch) {
+                continue;
+            }
+            let tiles_of_ch = || {
+                chars
+                    .iter()
+                    .zip(tiles)
+                    .filter(move |(&c, _)| c == ch)
+                    .map(|(_, &t)| t)
+            };
+            let found = tiles_of_ch().filter(|&t| t != Tile::Gray).count();
+            let count = self.counts.entry(ch).or_default();
+            count.min = count.min.max(found);
+            if tiles_of_ch().any(|t| t == Tile::Gray) {
+                // A black tile means there are no more copies than the green and yellow ones.
+                count.max = Some(count.max.map_or(found, |max| max.min(found)));
+            }
+        }
+    }
+
+    // Like `add_must_not_be`, but keeps a known character in that position.
+    fn add_must_not_be_unless_known(&mut self, pos: usize, ch: char) {
+        if !matches!(self.positional[pos], Some(PositionalFilter::MustBe(_))) {
+            self.add_must_not_be(pos, ch);
+        }
+    }
+
+    // The number of positions that must be the character.
+    fn known_positions(&self, ch: char) -> usize {
+        self.positional
+            .iter()
+            .filter(|p| matches!(p, Some(PositionalFilter::MustBe(c)) if *c == ch))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::{parse_row, pattern, tiles};
+
+    // Words with and without duplicate characters.
+    const WORDS: [&str; 14] = [
+        "abide", "speed", "geese", "those", "there", "eerie", "level", "hotel", "crane", "erase",
+        "sheep", "eagle", "ebbed", "sense",
+    ];
+
+    // The filter after entering the rows, e.g. `crane bygbb`.
+    fn filter(rows: &[&str]) -> Filter {
+        let mut filter = Filter::new(5);
+        for row in rows {
+            let (guess, boards) = parse_row(row, 5).unwrap();
+            filter.apply_feedback(&guess, &boards[0]);
+        }
+        filter
+    }
+
+    #[test]
+    fn gray_duplicate_of_green_character() {
+        // 'geese' for 'those'
+        let filter = filter(&["geese bbbgg"]);
+        assert_eq!(
+            filter.counts[&'e'],
+            LetterCount {
+                min: 1,
+                max: Some(1)
+            }
+        );
+        assert!(matches!(
+            filter.positional[4],
+            Some(PositionalFilter::MustBe('e'))
+        ));
+        assert!(filter.matches("those"));
+        assert!(!filter.matches("sense"));
+    }
+
+    #[test]
+    fn gray_duplicate_of_yellow_character() {
+        // 'speed' for 'abide'
+        let filter = filter(&["speed bbyby"]);
+        assert_eq!(
+            filter.counts[&'e'],
+            LetterCount {
+                min: 1,
+                max: Some(1)
+            }
+        );
+        assert_eq!(filter.counts[&'d'], LetterCount { min: 1, max: None });
+        assert!(filter.matches("abide"));
+        // the black 'e' is not in its position either
+        assert!(!filter.matches("abede"));
+        assert!(!filter.matches("eerie"));
+    }
+
+    #[test]
+    fn green_and_yellow_duplicates_count_as_minimum() {
+        // 'eerie' for 'there'
+        let filter = filter(&["eerie ybybg"]);
+        assert_eq!(
+            filter.counts[&'e'],
+            LetterCount {
+                min: 2,
+                max: Some(2)
+            }
+        );
+        assert!(filter.matches("there"));
+        assert!(!filter.matches("erase"));
+    }
+
+    #[test]
+    fn filter_matches_words_with_the_same_patterns() {
+        for answer in WORDS {
+            for first in WORDS {
+                for second in WORDS {
+                    let mut filter = Filter::new(5);
+                    for guess in [first, second] {
+                        filter.apply_feedback(guess, &tiles(pattern(guess, answer), 5));
+                    }
+                    for word in WORDS {
+                        let same_patterns = pattern(first, word) == pattern(first, answer)
+                            && pattern(second, word) == pattern(second, answer);
+                        assert_eq!(
+                            filter.matches(word),
+                            same_patterns,
+                            "'{}' after guessing '{}' and '{}' for '{}'",
+                            word,
+                            first,
+                            second,
+                            answer
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn must_not_occur_keeps_known_characters() {
+        let mut filter = Filter::new(5);
+        filter.set_must_be(0, 'e');
+        filter.add_must_not_occur('e');
+        assert_eq!(
+            filter.counts[&'e'],
+            LetterCount {
+                min: 1,
+                max: Some(1)
+            }
+        );
+        assert!(filter.matches("earth"));
+        assert!(!filter.matches("eagle"));
+    }
+}
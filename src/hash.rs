@@ -0,0 +1,24 @@
+//! A hash which is the same on every platform and with every version of Rust, for data saved
+//! to files or shared with others.
+
+/// Hashes the bytes with FNV-1a.
+pub fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_hashes() {
+        assert_eq!(fnv1a("".bytes()), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a".bytes()), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a("foobar".bytes()), 0x85944171f73967e8);
+    }
+}
@@ -0,0 +1,221 @@
+//! The words encoded for matching them against a filter without allocating.
+
+use crate::filter::{Filter, PositionalFilter};
+use anyhow::{bail, Result};
+
+/// The most different characters a word list may contain, one bit of a mask each.
+pub const MAX_CHARACTERS: usize = 64;
+
+/// Each character of each word as a small code, and the characters of each word as a bit mask.
+pub struct WordIndex {
+    word_length: usize,
+    /// The characters of all words, the code of a character is its index.
+    alphabet: Vec<char>,
+    /// The codes of the characters of all words, `word_length` codes per word.
+    codes: Vec<u8>,
+    /// The characters each word contains, with bit `n` for the character with code `n`.
+    masks: Vec<u64>,
+}
+
+impl WordIndex {
+    pub fn new<'a>(
+        words: impl IntoIterator<Item = &'a str>,
+        word_length: usize,
+    ) -> Result<WordIndex> {
+        let mut index = WordIndex {
+            word_length,
+            alphabet: vec![],
+            codes: vec![],
+            masks: vec![],
+        };
+        for word in words {
+            let mut mask = 0;
+            for ch in word.chars() {
+                let code = match index.code(ch) {
+                    Some(code) => code,
+                    None if index.alphabet.len() < MAX_CHARACTERS => {
+                        index.alphabet.push(ch);
+                        (index.alphabet.len() - 1) as u8
+                    }
+                    None => bail!(
+                        "the word list contains more than {} different characters",
+                        MAX_CHARACTERS
+                    ),
+                };
+                index.codes.push(code);
+                mask |= 1 << code;
+            }
+            index.masks.push(mask);
+        }
+        Ok(index)
+    }
+
+    /// Prepares the filter for matching words of the index.
+    pub fn query(&self, filter: &Filter) -> Query<'_> {
+        let mut query = Query {
+            index: self,
+            allowed: vec![u64::MAX; self.word_length],
+            required: 0,
+            forbidden: 0,
+            counts: vec![],
+            impossible: false,
+        };
+        for (allowed, p) in query.allowed.iter_mut().zip(&filter.positional) {
+            match p {
+                Some(PositionalFilter::MustBe(ch)) => match self.code(*ch) {
+                    Some(code) => *allowed = 1 << code,
+                    None => query.impossible = true,
+                },
+                Some(PositionalFilter::MustNotBe(chars)) => {
+                    for code in chars.iter().filter_map(|&ch| self.code(ch)) {
+                        *allowed &= !(1 << code);
+                    }
+                }
+                None => {}
+            }
+        }
+        for (&ch, count) in &filter.counts {
+            let Some(code) = self.code(ch) else {
+                // no word contains the character
+                query.impossible |= count.min > 0;
+                continue;
+            };
+            match (count.min, count.max) {
+                (_, Some(0)) => query.forbidden |= 1 << code,
+                (0, None) => {}
+                (1, None) => query.required |= 1 << code,
+                (min, max) => {
+                    if min > 0 {
+                        query.required |= 1 << code;
+                    }
+                    query.counts.push((code, min, max.unwrap_or(usize::MAX)));
+                }
+            }
+        }
+        query
+    }
+
+    // The code of a character, if any word contains it.
+    fn code(&self, ch: char) -> Option<u8> {
+        self.alphabet.iter().position(|&c| c == ch).map(|i| i as u8)
+    }
+}
+
+/// A filter prepared for matching the words of an index.
+pub struct Query<'a> {
+    index: &'a WordIndex,
+    /// The characters allowed in each position, as a mask.
+    allowed: Vec<u64>,
+    /// The characters which must occur.
+    required: u64,
+    /// The characters which must not occur.
+    forbidden: u64,
+    /// The code, minimum and maximum count of characters which must be counted.
+    counts: Vec<(u8, usize, usize)>,
+    /// The filter requires a character no word contains.
+    impossible: bool,
+}
+
+impl Query<'_> {
+    /// Whether the word with the index matches the filter.
+    pub fn matches(&self, word: usize) -> bool {
+        let mask = self.index.masks[word];
+        if self.impossible || mask & self.required != self.required || mask & self.forbidden != 0 {
+            return false;
+        }
+        let length = self.index.word_length;
+        let codes = &self.index.codes[word * length..(word + 1) * length];
+        codes
+            .iter()
+            .zip(&self.allowed)
+            .all(|(&code, allowed)| allowed & (1 << code) != 0)
+            && self.counts.iter().all(|&(code, min, max)| {
+                let n = codes.iter().filter(|&&c| c == code).count();
+                n >= min && n <= max
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        feedback::{pattern, tiles},
+        filter::{Constraint, LetterCount},
+    };
+
+    // Words with repeated characters, none of them contains a 'q'.
+    const WORDS: [&str; 12] = [
+        "speed", "geese", "eerie", "those", "there", "abide", "level", "hotel", "sheep", "ebbed",
+        "erase", "fizzy",
+    ];
+
+    fn count(min: usize, max: Option<usize>) -> LetterCount {
+        LetterCount { min, max }
+    }
+
+    // Filters with single constraints, including counts the masks alone cannot check.
+    fn filters() -> Vec<Filter> {
+        let constraints = [
+            Constraint::MustBe(0, 'e'),
+            Constraint::MustBe(2, 'q'),
+            Constraint::MustNotBe(4, 'e'),
+            Constraint::MustNotBe(1, 'q'),
+            Constraint::Count('e', count(2, None)),
+            Constraint::Count('e', count(0, Some(1))),
+            Constraint::Count('e', count(1, Some(1))),
+            Constraint::Count('e', count(2, Some(3))),
+            Constraint::Count('e', count(0, Some(0))),
+            Constraint::Count('z', count(1, None)),
+            Constraint::Count('q', count(1, None)),
+            Constraint::Count('q', count(0, Some(0))),
+        ];
+        let mut filters = vec![Filter::new(5)];
+        for constraint in constraints {
+            let mut filter = Filter::new(5);
+            filter.insert(constraint);
+            filters.push(filter);
+        }
+        let mut both = Filter::new(5);
+        both.insert(Constraint::MustBe(0, 'e'));
+        both.insert(Constraint::Count('e', count(0, Some(1))));
+        filters.push(both);
+        // the filters of a guess for each answer
+        for guess in WORDS {
+            for answer in WORDS {
+                let mut filter = Filter::new(5);
+                filter.apply_feedback(guess, &tiles(pattern(guess, answer), 5));
+                filters.push(filter);
+            }
+        }
+        filters
+    }
+
+    #[test]
+    fn query_matches_like_filter() {
+        let index = WordIndex::new(WORDS, 5).unwrap();
+        for filter in filters() {
+            let query = index.query(&filter);
+            for (i, word) in WORDS.iter().enumerate() {
+                assert_eq!(
+                    query.matches(i),
+                    filter.matches(word),
+                    "'{}' for {:?}",
+                    word,
+                    filter.constraints()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn too_many_characters() {
+        let words: Vec<String> = ('a'..='z')
+            .chain('\u{e0}'..='\u{ff}')
+            .chain('\u{100}'..='\u{17f}')
+            .map(|ch| ch.to_string())
+            .collect();
+        assert!(WordIndex::new(words.iter().map(|w| w.as_str()).take(64), 1).is_ok());
+        assert!(WordIndex::new(words.iter().map(|w| w.as_str()), 1).is_err());
+    }
+}
@@ -1,118 +1,53 @@
 //! A small helper to solve wordle puzzles.
 //!
-//! The app list all words that match the filter.
+//! The app list all words that match the filter, ranked by the chosen strategy.
 //!
 //! The filter can be modified with the following keyboard shortcuts:
 //! - `+` for 'character must occur'
 //! - `-` for 'must not occur'
-//! - `1-5` for 'must be in position'
+//! - `1-9` and `0` for 'must be in position' 1 to 10, or `F1` to `F11` for any position
 //! - `esc` or `*` for any position
 //! - any character to apply the chosen filter
+//! - `enter` to type a whole row: the guessed word and its colors, e.g. `crane bygbb`
+//! - `tab` to switch the ranking strategy
+//! - `!` to switch hard mode on or off
+//! - `#` to hide, demote or show answers of past puzzles
+//! - `<` and `>` to select the board filters are applied to, when playing on several boards
+//! - `backspace` or `ctrl-z` to undo the last change of the filter, `ctrl-y` to redo it
+//! - `delete` to remove a single filter by its number, `=` to change it
 
-use anyhow::Result;
+mod absurdle;
+mod args;
+mod batch;
+mod bench;
+mod daily;
+mod feedback;
+mod filter;
+mod hash;
+mod index;
+mod matrix;
+mod play;
+mod puzzle;
+mod rank;
+mod session;
+mod simulate;
+mod solver;
+mod tree;
+mod words;
+
+use anyhow::{bail, Result};
+use args::{Args, Command};
 use crossterm::{
     event::{self, KeyEvent, KeyEventKind},
     execute,
     style::{Color, Print, ResetColor, SetForegroundColor},
 };
-use std::{fs::read_to_string, io::stdout, path::Path};
-
-const WORD_LENGTH: usize = 5;
-/*
-Different filter types:
-- occurence, char must occur (possibly multiple times), or must not occur
-- positional, must be x or must not be x,y,z
-  - if must be x, existing 'must not be' filter can discarded
-  */
-#[derive(Debug, Clone)]
-enum PositionalFilter {
-    MustBe(char),
-    MustNotBe(Vec<char>),
-}
-
-#[derive(Debug, Clone)]
-struct Filter {
-    positional: Vec<Option<PositionalFilter>>,
-    must_occur: Vec<char>,
-    must_not_occur: Vec<char>,
-}
-
-impl Filter {
-    fn print(&self) {
-        let mut lines = vec![];
-        for (i, p) in self.positional.iter().enumerate() {
-            match p {
-                Some(PositionalFilter::MustBe(ch)) => {
-                    lines.push(format!("- char {} must be {}", i + 1, ch));
-                }
-                Some(PositionalFilter::MustNotBe(chars)) => {
-                    lines.push(format!("- char {} must not be {:?}", i + 1, chars));
-                }
-                None => {}
-            }
-        }
-        if !self.must_occur.is_empty() {
-            lines.push(format!("- word must contain: {:?}", self.must_occur));
-        }
-        if !self.must_not_occur.is_empty() {
-            lines.push(format!(
-                "- word must not contain: {:?}",
-                self.must_not_occur
-            ));
-        }
-        if !lines.is_empty() {
-            println!("Filter:\n{}", lines.join("\n"));
-        }
-    }
-
-    fn matches(&self, word: &str) -> bool {
-        for (i, c) in word.chars().enumerate() {
-            match self.positional[i] {
-                Some(PositionalFilter::MustBe(ch)) => {
-                    if c != ch {
-                        return false;
-                    }
-                }
-                Some(PositionalFilter::MustNotBe(ref chars)) => {
-                    if chars.contains(&c) {
-                        return false;
-                    }
-                }
-                None => {}
-            }
-        }
-        let mut w = word.to_string();
-        for c in &self.must_occur {
-            match w.find(*c) {
-                None => return false,
-                Some(i) => {
-                    // remove the matched character to properly match multiple identical characters
-                    w.replace_range(i..i + 1, "");
-                }
-            }
-        }
-        // apply the 'must not occur' filter only to characters that don't have a positional 'must be'
-        let masked_word = self
-            .positional
-            .iter()
-            .enumerate()
-            .filter(|(_, p)| !matches!(p, Some(PositionalFilter::MustBe(_))))
-            .map(|(i, _)| word.chars().nth(i).unwrap())
-            .collect::<String>();
-        for c in &self.must_not_occur {
-            if masked_word.contains(*c) {
-                return false;
-            }
-        }
-        true
-    }
-
-    fn is_empty(&self) -> bool {
-        self.positional.iter().all(|p| p.is_none())
-            && self.must_occur.is_empty()
-            && self.must_not_occur.is_empty()
-    }
-}
+use filter::{Constraint, Filter};
+use puzzle::Puzzle;
+use rank::{Candidate, Strategy};
+use solver::{rank_matches, Matches, Ranking, MAX_MATCHES_FOR_RANKING};
+use std::io::{stdout, Write};
+use words::WordList;
 
 // InputMode defines how character filters are applied:
 enum InputMode {
@@ -120,10 +55,28 @@ enum InputMode {
     Positional(usize, bool),
     // Global: character must occur (true) or must not occur (false)
     Global(bool),
+    // Replace: the character of the filter is replaced, the rest of the filter is kept
+    Replace(Constraint),
 }
 
 impl InputMode {
+    // The input mode to type the filter again.
+    fn typing(constraint: Constraint) -> InputMode {
+        match constraint {
+            Constraint::MustBe(pos, _) => InputMode::Positional(pos, true),
+            Constraint::MustNotBe(pos, _) => InputMode::Positional(pos, false),
+            Constraint::Count(_, count) => InputMode::Global(count.max != Some(0)),
+        }
+    }
+
     fn print(&self) {
+        if let InputMode::Replace(constraint) = self {
+            println!(
+                "Press any character to replace the one of '{}', esc to keep it",
+                constraint
+            );
+            return;
+        }
         print!("Press any charactor to filter on ");
         match self {
             InputMode::Positional(x, true) => {
@@ -138,75 +91,463 @@ impl InputMode {
             InputMode::Global(false) => {
                 println!("'word must not contain'");
             }
+            InputMode::Replace(_) => {}
         }
     }
 }
 
 const DEFAULT_INPUT_MODE: InputMode = InputMode::Global(false);
 
+// Options for solving a puzzle, most of them can be changed while solving.
+struct Options {
+    strategy: Strategy,
+    // In hard mode every guess must match the filter, so words that don't match are not suggested.
+    hard_mode: bool,
+    past_answers: PastAnswers,
+    // replace accented characters typed by their base character
+    fold_accents: bool,
+}
+
+// How answers of past puzzles are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PastAnswers {
+    Hide,
+    // listed after all other matches
+    Demote,
+    // for puzzles which repeat answers
+    Show,
+}
+
+impl PastAnswers {
+    fn next(self) -> PastAnswers {
+        match self {
+            PastAnswers::Hide => PastAnswers::Demote,
+            PastAnswers::Demote => PastAnswers::Show,
+            PastAnswers::Show => PastAnswers::Hide,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PastAnswers::Hide => "hidden",
+            PastAnswers::Demote => "listed last",
+            PastAnswers::Show => "listed like other words",
+        }
+    }
+}
+
 fn main() -> Result<()> {
-    println!("Reading word list...");
-    let words = read_words_from_file("words.txt", WORD_LENGTH)?;
-    let mut filter = Filter {
-        positional: vec![None; WORD_LENGTH],
-        must_occur: vec![],
-        must_not_occur: vec![],
+    let mut args = Args::parse(std::env::args().skip(1))?;
+    if args.help {
+        println!("{}", args::USAGE);
+        return Ok(());
+    }
+    let session = match args.command {
+        Command::Interactive => session::offer_resume(&args.session),
+        _ => None,
     };
+    // a resumed session is solved with the same word list
+    if let Some(session) = &session {
+        args.answers = session.answers.clone();
+        args.guesses = session.guesses.clone();
+        args.past = session.past.clone();
+        args.word_length = session.word_length;
+        args.fold_accents = session.fold_accents;
+        args.boards = session.puzzle.boards.len();
+    }
+    eprintln!("Reading word list...");
+    let word_length = args.word_length;
+    let mut words = WordList::load(
+        &args.answers,
+        args.guesses.as_ref(),
+        word_length,
+        args.fold_accents,
+    )?;
+    if let Some(past) = &args.past {
+        words.load_past_answers(past, word_length, args.fold_accents)?;
+    }
+    if let Some(patterns) = &args.patterns {
+        words.load_patterns(patterns)?;
+    }
+    match args.command {
+        Command::Interactive => {}
+        Command::Solve => {
+            let status = batch::solve(&words, &args)?;
+            _ = stdout().flush();
+            std::process::exit(status);
+        }
+        Command::Play => {
+            return match args.puzzle_number {
+                Some(puzzle_number) => play::play_daily(
+                    &words,
+                    puzzle_number,
+                    &args.salt,
+                    args.window,
+                    args.fold_accents,
+                ),
+                None => play::play(&words, args.fold_accents),
+            };
+        }
+        Command::Absurdle if args.solve => return absurdle::solve(&words, word_length, args.width),
+        Command::Absurdle => return absurdle::host(&words, word_length, args.fold_accents),
+        Command::Bench => return bench::bench(&words, word_length),
+        Command::Simulate | Command::Tournament | Command::Tree => {
+            let targets;
+            let answers: Vec<&str> = match &args.targets {
+                Some(file) => {
+                    targets = words::read_words(file, word_length, args.fold_accents)?;
+                    targets.iter().map(|w| w.as_str()).collect()
+                }
+                None => words.frequent_answers()?,
+            };
+            let mut openers = vec![];
+            for opener in &args.openers {
+                openers.push(simulate::openers(&words, opener, args.fold_accents)?);
+            }
+            if args.command == Command::Tournament {
+                let strategies = match args.strategy {
+                    Some(strategy) => vec![strategy],
+                    None => Strategy::ALL.to_vec(),
+                };
+                return simulate::tournament(
+                    &words,
+                    &answers,
+                    word_length,
+                    &strategies,
+                    args.hard_mode,
+                    &openers,
+                );
+            }
+            if openers.len() > 1 {
+                bail!("--opener can be given only once, except for a tournament");
+            }
+            if args.command == Command::Tree {
+                simulate::check_answers(&words, &answers)?;
+                return tree::export(
+                    &words,
+                    &answers,
+                    word_length,
+                    args.strategy.unwrap_or(Strategy::FileOrder),
+                    args.hard_mode,
+                    &openers.pop().unwrap_or_default(),
+                    args.format,
+                    args.output.as_deref(),
+                );
+            }
+            return simulate::simulate(
+                &words,
+                &answers,
+                word_length,
+                args.strategy.unwrap_or(Strategy::FileOrder),
+                args.hard_mode,
+                openers.pop().unwrap_or_default(),
+            );
+        }
+    }
+    // the well known start words of english wordle, if the word list has them
+    let english = vec!["slate", "carle", "stare", "roate"];
+    let start_words = if english
+        .iter()
+        .all(|&w| words.guesses.iter().any(|g| g.0 == w))
+    {
+        english
+    } else {
+        words.start_words(4)
+    };
+    let (mut puzzle, mut options) = match session {
+        Some(session) => (
+            session.puzzle,
+            Options {
+                strategy: session.strategy,
+                hard_mode: session.hard_mode,
+                past_answers: session.past_answers,
+                fold_accents: args.fold_accents,
+            },
+        ),
+        None => (
+            Puzzle::new(args.boards, word_length),
+            Options {
+                strategy: Strategy::FileOrder,
+                hard_mode: false,
+                past_answers: PastAnswers::Hide,
+                fold_accents: args.fold_accents,
+            },
+        ),
+    };
+    let mut matches: Vec<Matches> = puzzle.boards.iter().map(|_| Matches::default()).collect();
     let mut input_mode = DEFAULT_INPUT_MODE;
     loop {
-        if filter.is_empty() {
-            print_start_words();
+        if puzzle.is_empty() {
+            print_start_words(&start_words);
+        } else if puzzle.boards.len() == 1 {
+            print_word_list(&words, &puzzle.boards[0], &mut matches[0], &options, 10);
         } else {
-            print_word_list(&words, &filter, 10);
+            print_boards(&words, &puzzle, &mut matches, &options, 10);
+        }
+        if puzzle.boards.len() > 1 {
+            println!(
+                "Filters are applied to board {}, press < or > to change",
+                puzzle.active + 1
+            );
+        }
+        if !puzzle.rows.is_empty() {
+            println!("Rows entered: {}", puzzle.rows.join(", "));
+        }
+        puzzle.boards[puzzle.active].print();
+        println!("Press + for 'character must occur', - for 'must not occur', {} for 'must be in position', esc for any position, enter to type a whole row, backspace to undo, ctrl-y to redo, delete to remove a filter, = to change one", position_keys(word_length));
+        println!(
+            "Words are ranked by {}, press tab to change. Hard mode is {}, press ! to change",
+            options.strategy.name(),
+            if options.hard_mode { "on" } else { "off" }
+        );
+        if !words.past_answers.is_empty() {
+            println!(
+                "Past answers are {}, press # to change",
+                options.past_answers.name()
+            );
         }
-        filter.print();
-        println!("Press + for 'character must occur', - for 'must not occur', 1-5 for 'must be in position', esc for any position");
         input_mode.print();
-        input_mode = process_input(input_mode, &mut filter);
+        input_mode = process_input(input_mode, &mut puzzle, &mut options);
+        if let Err(e) = session::save(&args.session, &args, &puzzle, &options) {
+            colored_print(Color::Red, &format!("Session not saved: {:#}\n", e));
+        }
     }
 }
 
-fn print_word_list(words: &[(String, bool)], filter: &Filter, max_words: usize) {
-    let mut matches = vec![];
-    let mut rare = vec![];
-    for word in words {
-        if filter.matches(&word.0) {
-            if word.1 {
-                matches.push(word);
-            } else {
-                rare.push(word);
-            }
-            if matches.len() >= max_words {
-                break;
-            }
-        }
-    }
-    let matches: Vec<(String, bool)> = matches
-        .iter()
-        .chain(rare.iter())
-        .take(max_words)
-        .map(|w| (w.0.to_owned(), w.1))
-        .collect();
+fn print_word_list(
+    words: &WordList,
+    filter: &Filter,
+    known_matches: &mut Matches,
+    options: &Options,
+    max_words: usize,
+) {
+    let (matches, hidden) = matching_words(words, filter, known_matches, options.past_answers);
+    let Ranking {
+        strategy,
+        guesses,
+        suggestions: ranked,
+        ..
+    } = rank_matches(
+        words,
+        &[(filter, &matches)],
+        options.strategy,
+        options.hard_mode,
+        MAX_MATCHES_FOR_RANKING,
+    );
+    let is_past = |word: &str| words.past_answers.contains(word);
+    let weight = |i: usize| Candidate::new(i, words.answers[i].1).weight;
+    let total_weight: f64 = matches.iter().map(|&i| weight(i)).sum();
     println!();
+    if strategy != options.strategy {
+        print_too_many_to_rank(options.strategy);
+    }
     if matches.is_empty() {
         colored_print(Color::Red, "No matches\n");
     } else {
-        println!("Matches:");
-        for m in &matches {
+        // Probes ranked before the best match are better guesses than any match.
+        // Matches come first in the guesses, so they are ranked first if the score is equal.
+        let probes: Vec<_> = ranked
+            .iter()
+            .take_while(|suggestion| suggestion.index >= matches.len())
+            .take(3)
+            .collect();
+        if !probes.is_empty() {
+            println!("Better guesses to eliminate matches:");
+            for suggestion in probes {
+                let score = strategy.format_score(suggestion.score).unwrap_or_default();
+                colored_print(
+                    Color::Cyan,
+                    &format!(
+                        "- {} ({})\n",
+                        words.guesses[guesses[suggestion.index]].0, score
+                    ),
+                );
+            }
+        }
+        if hidden > 0 {
+            println!(
+                "Matches ({}, {} past answers hidden):",
+                matches.len(),
+                hidden
+            );
+        } else {
+            println!("Matches ({}):", matches.len());
+        }
+        let mut listed: Vec<_> = ranked
+            .iter()
+            .filter(|suggestion| suggestion.index < matches.len())
+            .collect();
+        if options.past_answers == PastAnswers::Demote {
+            listed.sort_by_key(|suggestion| is_past(&words.answers[matches[suggestion.index]].0));
+        }
+        for suggestion in listed.into_iter().take(max_words) {
+            let m = &words.answers[matches[suggestion.index]];
+            let past = options.past_answers == PastAnswers::Demote && is_past(&m.0);
             let color = if matches.len() == 1 {
                 Color::Green
+            } else if past {
+                Color::DarkYellow
             } else if m.1 {
                 Color::White
             } else {
                 Color::DarkGrey
             };
-            colored_print(color, &format!("- {}\n", m.0));
+            // the chance this word is the answer
+            let chance = weight(matches[suggestion.index]) / total_weight;
+            let mut details = match strategy.format_score(suggestion.score) {
+                Some(score) => format!("{}, {:.1}%", score, chance * 100.0),
+                None => format!("{:.1}%", chance * 100.0),
+            };
+            if past {
+                details.push_str(", past answer");
+            }
+            colored_print(color, &format!("- {} ({})\n", m.0, details));
+        }
+    }
+}
+
+// Prints the matches of each board side by side, followed by the best guesses for all boards.
+fn print_boards(
+    words: &WordList,
+    puzzle: &Puzzle,
+    known_matches: &mut [Matches],
+    options: &Options,
+    max_words: usize,
+) {
+    let word_length = puzzle.word_length();
+    let boards: Vec<Vec<usize>> = puzzle
+        .boards
+        .iter()
+        .zip(known_matches)
+        .map(|(filter, known)| matching_words(words, filter, known, options.past_answers).0)
+        .collect();
+    let unsolved = puzzle.unsolved();
+    let unsolved_boards: Vec<(&Filter, &[usize])> = unsolved
+        .iter()
+        .map(|&i| (&puzzle.boards[i], boards[i].as_slice()))
+        .collect();
+    let Ranking {
+        strategy,
+        guesses,
+        match_count,
+        suggestions: ranked,
+    } = rank_matches(
+        words,
+        &unsolved_boards,
+        options.strategy,
+        options.hard_mode,
+        MAX_MATCHES_FOR_RANKING,
+    );
+
+    println!();
+    let headers: Vec<String> = boards
+        .iter()
+        .enumerate()
+        .map(|(i, board)| {
+            let active = if i == puzzle.active { "*" } else { "" };
+            if puzzle.boards[i].is_solved() {
+                format!("Board {}{}: solved", i + 1, active)
+            } else {
+                format!("Board {}{} ({})", i + 1, active, board.len())
+            }
+        })
+        .collect();
+    // wide enough for the longest header, word and "No matches", with a gap between columns
+    let width = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .chain([word_length + 2, "No matches".len()])
+        .max()
+        .unwrap_or(0)
+        + 2;
+    for header in &headers {
+        print!("{:<width$}", header, width = width);
+    }
+    println!();
+    for row in 0..max_words {
+        for (i, board) in boards.iter().enumerate() {
+            let word = board.get(row).map(|&w| &words.answers[w]);
+            let cell = match word {
+                Some(w) => format!("{:<width$}", format!("- {}", w.0), width = width),
+                None if row == 0 => format!("{:<width$}", "No matches", width = width),
+                None => " ".repeat(width),
+            };
+            let color = match word {
+                _ if puzzle.boards[i].is_solved() || board.len() == 1 => Color::Green,
+                None => Color::Red,
+                Some(w)
+                    if options.past_answers == PastAnswers::Demote
+                        && words.past_answers.contains(&w.0) =>
+                {
+                    Color::DarkYellow
+                }
+                Some(w) if w.1 => Color::White,
+                Some(_) => Color::DarkGrey,
+            };
+            colored_print(color, &cell);
         }
+        println!();
     }
+    if !ranked.is_empty() && !unsolved.is_empty() {
+        if strategy != options.strategy {
+            print_too_many_to_rank(options.strategy);
+        }
+        println!("Best guesses for all boards:");
+        for suggestion in ranked.iter().take(max_words) {
+            let mut details = strategy.format_score(suggestion.score).unwrap_or_default();
+            if suggestion.index < match_count {
+                let on_boards: Vec<String> = boards
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, b)| b.contains(&guesses[suggestion.index]))
+                    .map(|(i, _)| (i + 1).to_string())
+                    .collect();
+                if !details.is_empty() {
+                    details.push_str(", ");
+                }
+                details.push_str(&format!("match on board {}", on_boards.join(", ")));
+            }
+            let color = if suggestion.index < match_count {
+                Color::White
+            } else {
+                Color::Cyan
+            };
+            let guess = &words.guesses[guesses[suggestion.index]].0;
+            if details.is_empty() {
+                colored_print(color, &format!("- {}\n", guess));
+            } else {
+                colored_print(color, &format!("- {} ({})\n", guess, details));
+            }
+        }
+    }
+}
+
+// The answers matching the filter as indices into the answers, frequent words first, and the
+// number of hidden past answers.
+fn matching_words(
+    words: &WordList,
+    filter: &Filter,
+    known_matches: &mut Matches,
+    past_answers: PastAnswers,
+) -> (Vec<usize>, usize) {
+    let mut matches = known_matches.update(words, filter).to_vec();
+    let all_matches = matches.len();
+    if past_answers == PastAnswers::Hide {
+        matches.retain(|&i| !words.past_answers.contains(&words.answers[i].0));
+    }
+    let hidden = all_matches - matches.len();
+    (matches, hidden)
+}
+
+// Tells that the matches are not ranked by the chosen strategy, see `MAX_MATCHES_FOR_RANKING`.
+fn print_too_many_to_rank(strategy: Strategy) {
+    println!(
+        "Too many matches to rank by {}, they are listed in word list order",
+        strategy.name()
+    );
 }
 
-fn print_start_words() {
-    let words = ["slate", "carle", "stare", "roate"];
+fn print_start_words(words: &[&str]) {
     println!(
         "No filter defined yet. Good starting words:\n- {}",
         words.join("\n- ")
@@ -217,67 +558,134 @@ fn colored_print(c: Color, s: &str) {
     _ = execute!(stdout(), SetForegroundColor(c), Print(s), ResetColor);
 }
 
-fn process_input(input_mode: InputMode, filter: &mut Filter) -> InputMode {
+fn process_input(input_mode: InputMode, puzzle: &mut Puzzle, options: &mut Options) -> InputMode {
     let key = read_key();
-    if key.modifiers != event::KeyModifiers::NONE {
+    // user undoes or redoes the last change of the filters
+    if key.modifiers == event::KeyModifiers::CONTROL {
+        match key.code {
+            event::KeyCode::Char('z') => undo(puzzle),
+            event::KeyCode::Char('y') => redo(puzzle),
+            _ => println!("Invalid input"),
+        }
+        return input_mode;
+    }
+    // shift is needed to type characters like `!` on many keyboards
+    if !event::KeyModifiers::SHIFT.contains(key.modifiers) {
         println!("Invalid input");
         return input_mode;
     }
+    let word_length = puzzle.word_length();
+    // user selects a position to filter on
+    if let Some(pos) = position(key.code, word_length) {
+        let must = match input_mode {
+            InputMode::Positional(_, x) => x,
+            InputMode::Global(x) => x,
+            InputMode::Replace(_) => false,
+        };
+        return InputMode::Positional(pos, must);
+    }
     match key.code {
         // user selects to filter on 'must occur' or 'must not occur'
         event::KeyCode::Char('+') | event::KeyCode::Char('-') => {
             let must = key.code == event::KeyCode::Char('+');
             match input_mode {
                 InputMode::Positional(x, _) => InputMode::Positional(x, must),
-                InputMode::Global(_) => InputMode::Global(must),
+                InputMode::Global(_) | InputMode::Replace(_) => InputMode::Global(must),
             }
         }
-        // user selects a position to filter on
-        event::KeyCode::Char(ch) if ('1'..='5').contains(&ch) => {
-            let pos = ch.to_digit(10).unwrap() as usize - 1;
-            let must = match input_mode {
-                InputMode::Positional(_, x) => x,
-                InputMode::Global(x) => x,
-            };
-            InputMode::Positional(pos, must)
+        // user undoes the last change of the filters
+        event::KeyCode::Backspace => {
+            undo(puzzle);
+            input_mode
+        }
+        // user removes a single filter
+        event::KeyCode::Delete => {
+            if let Some(constraint) = select_constraint(puzzle, "remove") {
+                puzzle.change_filter(|filter| filter.remove(&constraint));
+            }
+            input_mode
         }
+        // user changes a single filter: the next character typed replaces its character
+        event::KeyCode::Char('=') => match select_constraint(puzzle, "change") {
+            Some(constraint) => InputMode::Replace(constraint),
+            None => input_mode,
+        },
         // user selects to filter globally
         event::KeyCode::Esc | event::KeyCode::Char('*') => DEFAULT_INPUT_MODE,
+        // user selects the board filters are applied to
+        event::KeyCode::Char('<') | event::KeyCode::Char('>') => {
+            puzzle.select_next(key.code == event::KeyCode::Char('>'));
+            input_mode
+        }
+        // user switches how past answers are listed
+        event::KeyCode::Char('#') => {
+            options.past_answers = options.past_answers.next();
+            input_mode
+        }
+        // user switches hard mode on or off
+        event::KeyCode::Char('!') => {
+            options.hard_mode = !options.hard_mode;
+            input_mode
+        }
+        // user switches the ranking strategy
+        event::KeyCode::Tab => {
+            options.strategy = options.strategy.next();
+            input_mode
+        }
+        // user enters a guessed word and the colors wordle showed for it
+        event::KeyCode::Enter => {
+            if puzzle.boards.len() == 1 {
+                println!("Enter the guessed word and its colors (g=green, y=yellow, b=black), e.g. 'crane bygbb':");
+            } else {
+                println!("Enter the guessed word and its colors (g=green, y=yellow, b=black) on each unsolved board, e.g. 'crane bygbb gggbb':");
+            }
+            let result =
+                feedback::parse_row(&read_line(), word_length).and_then(|(guess, tiles)| {
+                    let guess = words::normalize(&guess, options.fold_accents);
+                    puzzle.apply_feedback(&guess, &tiles)
+                });
+            if let Err(e) = result {
+                colored_print(Color::Red, &format!("Invalid input: {}\n", e));
+            }
+            input_mode
+        }
         // user selects a character to filter on
-        event::KeyCode::Char(ch) if ch.is_ascii_lowercase() => {
-            match input_mode {
+        event::KeyCode::Char(ch) if ch.is_alphabetic() => {
+            let ch = ch.to_lowercase().next().unwrap_or(ch);
+            let ch = if options.fold_accents {
+                words::fold_accent(ch)
+            } else {
+                ch
+            };
+            if let InputMode::Replace(constraint) = input_mode {
+                // the filter may have been undone or another board selected meanwhile
+                if !puzzle.boards[puzzle.active]
+                    .constraints()
+                    .contains(&constraint)
+                {
+                    println!("The filter to change no longer exists");
+                    return DEFAULT_INPUT_MODE;
+                }
+            }
+            puzzle.change_filter(|filter| match input_mode {
                 InputMode::Positional(x, true) => {
                     // Filter is 'in position x, character must be y'.
-                    // Any possibly existing positional filter can be discarded.
-                    filter.positional[x] = Some(PositionalFilter::MustBe(ch));
+                    filter.set_must_be(x, ch);
                 }
                 InputMode::Positional(x, false) => {
                     // Filter is 'in position x, character must not be y'.
-                    match filter.positional[x] {
-                        None | Some(PositionalFilter::MustBe(_)) => {
-                            filter.positional[x] = Some(PositionalFilter::MustNotBe(vec![ch]));
-                        }
-                        Some(PositionalFilter::MustNotBe(ref mut vec)) => {
-                            vec.push(ch);
-                            vec.sort();
-                        }
-                    }
+                    filter.add_must_not_be(x, ch);
                     // add the character to the 'must occur' list, as the yellow indicator in wordle means character is in the word, but not at position
-                    if !filter.must_occur.contains(&ch) {
-                        filter.must_occur.push(ch);
-                        filter.must_occur.sort();
-                    }
-                }
-                InputMode::Global(true) => {
-                    filter.must_occur.push(ch);
-                    filter.must_occur.sort();
-                }
-                InputMode::Global(false) => {
-                    filter.must_not_occur.push(ch);
-                    filter.must_not_occur.sort();
+                    filter.set_min_count(ch, 1);
                 }
+                InputMode::Global(true) => filter.add_must_occur(ch),
+                InputMode::Global(false) => filter.add_must_not_occur(ch),
+                InputMode::Replace(constraint) => filter.replace_char(&constraint, ch),
+            });
+            match input_mode {
+                InputMode::Replace(constraint) => InputMode::typing(constraint),
+                input_mode => input_mode,
             }
-            input_mode
         }
         // invalid input
         _ => {
@@ -287,17 +695,61 @@ fn process_input(input_mode: InputMode, filter: &mut Filter) -> InputMode {
     }
 }
 
-fn read_words_from_file(
-    filename: impl AsRef<Path>,
-    word_length: usize,
-) -> Result<Vec<(String, bool)>> {
-    // The file is expected to contain words with a leading + or -.
-    // A + indicates a frequent word.
-    Ok(read_to_string(filename)?
-        .lines()
-        .filter(|x| x.len() == word_length + 1)
-        .map(|s| (s[1..].to_string(), s.starts_with('+')))
-        .collect())
+// Asks for the number of a filter of the active board, as listed by `Filter::print`.
+fn select_constraint(puzzle: &Puzzle, action: &str) -> Option<Constraint> {
+    let constraints = puzzle.boards[puzzle.active].constraints();
+    if constraints.is_empty() {
+        println!("There is no filter to {}", action);
+        return None;
+    }
+    println!("Enter the number of the filter to {}:", action);
+    let line = read_line();
+    match line.trim().parse::<usize>() {
+        Ok(n) if (1..=constraints.len()).contains(&n) => Some(constraints[n - 1]),
+        _ => {
+            colored_print(
+                Color::Red,
+                &format!(
+                    "Invalid input: '{}' is not a number from 1 to {}\n",
+                    line.trim(),
+                    constraints.len()
+                ),
+            );
+            None
+        }
+    }
+}
+
+fn undo(puzzle: &mut Puzzle) {
+    if !puzzle.undo() {
+        println!("Nothing to undo");
+    }
+}
+
+fn redo(puzzle: &mut Puzzle) {
+    if !puzzle.redo() {
+        println!("Nothing to redo");
+    }
+}
+
+// The position selected by a key: 1-9 and 0 for positions 1 to 10, F1 to F11 for any position.
+fn position(code: event::KeyCode, word_length: usize) -> Option<usize> {
+    let pos = match code {
+        event::KeyCode::Char('0') => 9,
+        event::KeyCode::Char(ch @ '1'..='9') => ch.to_digit(10).unwrap() as usize - 1,
+        event::KeyCode::F(n) if n > 0 => n as usize - 1,
+        _ => return None,
+    };
+    (pos < word_length).then_some(pos)
+}
+
+// Describes the keys to select a position, for the help text.
+fn position_keys(word_length: usize) -> String {
+    match word_length {
+        ..=9 => format!("1-{}", word_length),
+        10 => "1-9 and 0".to_string(),
+        _ => format!("1-9, 0 and F1-F{}", word_length),
+    }
 }
 
 pub fn read_key() -> KeyEvent {
@@ -310,3 +762,23 @@ pub fn read_key() -> KeyEvent {
         }
     }
 }
+
+// Reads a line of input, echoing the typed characters. Returns when enter is pressed.
+fn read_line() -> String {
+    let mut line = String::new();
+    loop {
+        let key = read_key();
+        match key.code {
+            event::KeyCode::Enter => break,
+            event::KeyCode::Backspace if line.pop().is_some() => print!("\u{8} \u{8}"),
+            event::KeyCode::Char(ch) => {
+                line.push(ch);
+                print!("{}", ch);
+            }
+            _ => {}
+        }
+        _ = stdout().flush();
+    }
+    println!();
+    line
+}
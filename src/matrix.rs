@@ -0,0 +1,182 @@
+//! The feedback pattern of every guess for every answer, cached in a file.
+
+use crate::{
+    feedback::{pattern, Pattern},
+    hash::fnv1a,
+    words::WordList,
+};
+use anyhow::{Context, Result};
+use std::{fs, path::Path};
+
+/// Identifies the file format, changed when the format changes.
+const MAGIC: &[u8; 8] = b"WDLPAT01";
+/// Magic, hash of the word list, number of guesses and answers and bytes per pattern.
+const HEADER_SIZE: usize = 8 + 8 + 4 + 4 + 1;
+
+/// The patterns of all guesses for all answers, as compact as the word length allows.
+pub struct PatternMatrix {
+    answers: usize,
+    /// Bytes per pattern: 1 for words up to 5 characters, 2 up to 10 and 4 otherwise.
+    width: usize,
+    data: Vec<u8>,
+}
+
+impl PatternMatrix {
+    /// Loads the matrix from the file, or computes and saves it if the file is missing or was
+    /// computed for another word list.
+    pub fn load_or_build(filename: impl AsRef<Path>, words: &WordList) -> Result<PatternMatrix> {
+        let filename = filename.as_ref();
+        let hash = hash(words);
+        if let Some(matrix) = PatternMatrix::load(filename, hash, words) {
+            return Ok(matrix);
+        }
+        eprintln!("Computing patterns for {}...", filename.display());
+        let matrix = PatternMatrix::build(words);
+        let mut file = Vec::with_capacity(HEADER_SIZE + matrix.data.len());
+        file.extend_from_slice(MAGIC);
+        file.extend_from_slice(&hash.to_le_bytes());
+        file.extend_from_slice(&(words.guesses.len() as u32).to_le_bytes());
+        file.extend_from_slice(&(words.answers.len() as u32).to_le_bytes());
+        file.push(matrix.width as u8);
+        file.extend_from_slice(&matrix.data);
+        fs::write(filename, file)
+            .with_context(|| format!("failed to write {}", filename.display()))?;
+        Ok(matrix)
+    }
+
+    // The matrix of the file, if it is valid for the word list.
+    fn load(filename: &Path, hash: u64, words: &WordList) -> Option<PatternMatrix> {
+        let mut data = fs::read(filename).ok()?;
+        let header = data.get(..HEADER_SIZE)?;
+        let width = width(words);
+        let expected_size = HEADER_SIZE + words.guesses.len() * words.answers.len() * width;
+        if &header[..8] != MAGIC
+            || header[8..16] != hash.to_le_bytes()
+            || header[16..20] != (words.guesses.len() as u32).to_le_bytes()
+            || header[20..24] != (words.answers.len() as u32).to_le_bytes()
+            || header[24] as usize != width
+            || data.len() != expected_size
+        {
+            return None;
+        }
+        data.drain(..HEADER_SIZE);
+        Some(PatternMatrix {
+            answers: words.answers.len(),
+            width,
+            data,
+        })
+    }
+
+    /// Computes the patterns of all guesses for all answers.
+    pub fn build(words: &WordList) -> PatternMatrix {
+        let width = width(words);
+        let mut data = Vec::with_capacity(words.guesses.len() * words.answers.len() * width);
+        for guess in &words.guesses {
+            for answer in &words.answers {
+                let pattern = pattern(&guess.0, &answer.0).to_le_bytes();
+                data.extend_from_slice(&pattern[..width]);
+            }
+        }
+        PatternMatrix {
+            answers: words.answers.len(),
+            width,
+            data,
+        }
+    }
+
+    /// The pattern of a guess for an answer, by their index in the word list.
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        let start = (guess * self.answers + answer) * self.width;
+        match self.width {
+            1 => self.data[start] as Pattern,
+            2 => u16::from_le_bytes([self.data[start], self.data[start + 1]]) as Pattern,
+            _ => Pattern::from_le_bytes([
+                self.data[start],
+                self.data[start + 1],
+                self.data[start + 2],
+                self.data[start + 3],
+            ]),
+        }
+    }
+}
+
+// Bytes needed for a pattern of the word list: 3^5 patterns fit into a byte, 3^10 into two.
+fn width(words: &WordList) -> usize {
+    match words.guesses.first().map_or(0, |w| w.0.chars().count()) {
+        0..=5 => 1,
+        6..=10 => 2,
+        _ => 4,
+    }
+}
+
+// A hash of all words in their order.
+fn hash(words: &WordList) -> u64 {
+    let answers = (words.answers.len() as u64).to_le_bytes();
+    let guesses = words
+        .guesses
+        .iter()
+        .flat_map(|w| w.0.bytes().chain([b'\n']));
+    fnv1a(answers.into_iter().chain(guesses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::TempFile;
+
+    fn assert_patterns(matrix: &PatternMatrix, words: &WordList) {
+        for (g, guess) in words.guesses.iter().enumerate() {
+            for (a, answer) in words.answers.iter().enumerate() {
+                assert_eq!(matrix.get(g, a), pattern(&guess.0, &answer.0));
+            }
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let words = WordList::of(&["hills", "pills"], &["fills"], &["crane", "spill"]);
+        let file = TempFile::new("matrix-round-trip.bin", "");
+        let built = PatternMatrix::load_or_build(&file.0, &words).unwrap();
+        assert_patterns(&built, &words);
+
+        // a valid file is loaded, not computed again
+        let mut data = fs::read(&file.0).unwrap();
+        data[HEADER_SIZE] ^= 1;
+        fs::write(&file.0, &data).unwrap();
+        let loaded = PatternMatrix::load_or_build(&file.0, &words).unwrap();
+        assert_eq!(loaded.get(0, 0), built.get(0, 0) ^ 1);
+        data[HEADER_SIZE] ^= 1;
+        fs::write(&file.0, &data).unwrap();
+        assert_patterns(
+            &PatternMatrix::load(&file.0, hash(&words), &words).unwrap(),
+            &words,
+        );
+    }
+
+    #[test]
+    fn rebuild_invalid_files() {
+        let words = WordList::of(&["hills", "pills"], &["fills"], &["crane", "spill"]);
+        let file = TempFile::new("matrix-rebuild.bin", "");
+        PatternMatrix::load_or_build(&file.0, &words).unwrap();
+        let size = fs::read(&file.0).unwrap().len();
+
+        // another word list, with the same number of words
+        let other = WordList::of(&["hills", "kills"], &["fills"], &["crane", "spill"]);
+        assert!(PatternMatrix::load(&file.0, hash(&other), &other).is_none());
+        assert_patterns(
+            &PatternMatrix::load_or_build(&file.0, &other).unwrap(),
+            &other,
+        );
+        assert!(PatternMatrix::load(&file.0, hash(&other), &other).is_some());
+
+        // a truncated file
+        let data = fs::read(&file.0).unwrap();
+        fs::write(&file.0, &data[..size - 1]).unwrap();
+        assert!(PatternMatrix::load(&file.0, hash(&other), &other).is_none());
+        assert_patterns(
+            &PatternMatrix::load_or_build(&file.0, &other).unwrap(),
+            &other,
+        );
+        assert_eq!(fs::read(&file.0).unwrap(), data);
+    }
+}
@@ -0,0 +1,125 @@
+//! A game with a hidden answer, to practice offline.
+
+use crate::{
+    colored_print,
+    daily::{self, Day},
+    feedback::{pattern, tiles, Tile},
+    read_line,
+    words::{normalize, WordList},
+};
+use anyhow::{bail, Result};
+use crossterm::style::Color;
+use std::{
+    collections::{hash_map::RandomState, HashSet},
+    hash::{BuildHasher, Hasher},
+    io::{stdout, Write},
+};
+
+pub const MAX_GUESSES: usize = 6;
+
+/// Picks a random frequent word, which is not a past answer, and lets the user guess it.
+pub fn play(words: &WordList, fold_accents: bool) -> Result<()> {
+    let mut secrets = words.frequent_answers()?;
+    secrets.retain(|&w| !words.past_answers.contains(w));
+    if secrets.is_empty() {
+        bail!("all frequent words are past answers");
+    }
+    // the hasher is randomly seeded for each process
+    let random = RandomState::new().build_hasher().finish();
+    let answer = secrets[(random % secrets.len() as u64) as usize];
+    guess_answer(words, answer, fold_accents);
+    Ok(())
+}
+
+/// Lets the user guess the answer of a daily puzzle.
+///
+/// The answer is picked from the frequent words in the order of the word list, so everyone
+/// using the same word list and salt gets the same puzzle. Past answers are not considered.
+pub fn play_daily(
+    words: &WordList,
+    puzzle_number: Day,
+    salt: &str,
+    window: usize,
+    fold_accents: bool,
+) -> Result<()> {
+    let answer = daily::answer(&words.frequent_answers()?, puzzle_number, salt, window)?;
+    println!(
+        "Daily puzzle {} of {}",
+        puzzle_number,
+        daily::format_date(daily::FIRST_DAY + puzzle_number)
+    );
+    let result = match guess_answer(words, answer, fold_accents) {
+        Some(guesses) => guesses.to_string(),
+        None => "X".to_string(),
+    };
+    println!("Puzzle {}: {}/{}", puzzle_number, result, MAX_GUESSES);
+    Ok(())
+}
+
+/// Lets the user guess the answer, printing the tiles for each guess.
+///
+/// Returns the number of guesses needed, or `None` if the answer was not found.
+pub fn guess_answer(words: &WordList, answer: &str, fold_accents: bool) -> Option<usize> {
+    let word_length = answer.chars().count();
+    let valid: HashSet<&str> = words.guesses.iter().map(|w| w.0.as_str()).collect();
+    println!(
+        "Guess the word of {} characters, you have {} guesses.",
+        word_length, MAX_GUESSES
+    );
+    let mut guesses = 0;
+    while guesses < MAX_GUESSES {
+        let prompt = format!("Guess {} of {}: ", guesses + 1, MAX_GUESSES);
+        let guess = read_guess(&prompt, &valid, word_length, fold_accents);
+        guesses += 1;
+        print_tiles(&guess, &tiles(pattern(&guess, answer), word_length));
+        if guess == answer {
+            colored_print(
+                Color::Green,
+                &format!("You found the word with {} guesses!\n", guesses),
+            );
+            return Some(guesses);
+        }
+    }
+    colored_print(Color::Red, &format!("The word was '{}'\n", answer));
+    None
+}
+
+/// Reads a guess until the user enters a word from the word list.
+pub fn read_guess(
+    prompt: &str,
+    valid: &HashSet<&str>,
+    word_length: usize,
+    fold_accents: bool,
+) -> String {
+    loop {
+        print!("{}", prompt);
+        _ = stdout().flush();
+        let guess = normalize(read_line().trim(), fold_accents);
+        if guess.chars().count() != word_length {
+            colored_print(
+                Color::Red,
+                &format!("The word must have {} characters\n", word_length),
+            );
+        } else if !valid.contains(guess.as_str()) {
+            colored_print(
+                Color::Red,
+                &format!("'{}' is not in the word list\n", guess),
+            );
+        } else {
+            return guess;
+        }
+    }
+}
+
+/// Prints a guessed word with each character colored like its tile.
+pub fn print_tiles(word: &str, tiles: &[Tile]) {
+    for (ch, tile) in word.chars().zip(tiles) {
+        let color = match tile {
+            Tile::Green => Color::Green,
+            Tile::Yellow => Color::Yellow,
+            Tile::Gray => Color::DarkGrey,
+        };
+        colored_print(color, &format!(" {} ", ch.to_uppercase()));
+    }
+    println!();
+}
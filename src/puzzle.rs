@@ -0,0 +1,224 @@
+//! The puzzle being solved, on one or more boards.
+
+use crate::{feedback::Tile, filter::Filter};
+use anyhow::{bail, Result};
+
+/// One filter per board. Every guess is scored against the hidden word of each board.
+pub struct Puzzle {
+    pub boards: Vec<Filter>,
+    /// The board that filters typed one by one are applied to.
+    pub active: usize,
+    /// The rows entered so far, e.g. `crane bygbb`.
+    pub rows: Vec<String>,
+    /// The filters of all boards and the rows before each change, the last change last.
+    undo: Vec<(Vec<Filter>, Vec<String>)>,
+    /// The filters of all boards and the rows before each change that was undone.
+    redo: Vec<(Vec<Filter>, Vec<String>)>,
+}
+
+impl Puzzle {
+    pub fn new(boards: usize, word_length: usize) -> Puzzle {
+        Puzzle {
+            boards: vec![Filter::new(word_length); boards],
+            active: 0,
+            rows: vec![],
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+
+    pub fn word_length(&self) -> usize {
+        self.boards[0].positional.len()
+    }
+
+    /// Changes the filter of the active board. The change can be undone, unless the filter
+    /// stays the same, e.g. when a filter is typed again.
+    pub fn change_filter(&mut self, change: impl FnOnce(&mut Filter)) {
+        let before = self.boards.clone();
+        change(&mut self.boards[self.active]);
+        if self.boards[self.active].constraints() != before[self.active].constraints() {
+            self.undo.push((before, self.rows.clone()));
+            self.redo.clear();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boards.iter().all(|board| board.is_empty())
+    }
+
+    /// The boards whose word is not known yet.
+    pub fn unsolved(&self) -> Vec<usize> {
+        (0..self.boards.len())
+            .filter(|&i| !self.boards[i].is_solved())
+            .collect()
+    }
+
+    /// Derives the filters of all boards from a guessed word and the tiles shown on each board.
+    ///
+    /// The tiles can be given for all boards or only for the boards which are not solved yet.
+    pub fn apply_feedback(&mut self, guess: &str, tiles: &[Vec<Tile>]) -> Result<()> {
+        let boards = if tiles.len() == self.boards.len() {
+            (0..self.boards.len()).collect()
+        } else {
+            self.unsolved()
+        };
+        if tiles.len() != boards.len() {
+            bail!(
+                "expected the colors of {} boards, got {}",
+                boards.len(),
+                tiles.len()
+            );
+        }
+        self.remember();
+        let colors: Vec<String> = tiles
+            .iter()
+            .map(|tiles| tiles.iter().map(|tile| tile.to_char()).collect())
+            .collect();
+        self.rows.push(format!("{} {}", guess, colors.join(" ")));
+        for (board, tiles) in boards.into_iter().zip(tiles) {
+            self.boards[board].apply_feedback(guess, tiles);
+        }
+        Ok(())
+    }
+
+    /// Restores the filters before the last change. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(state) => {
+                let state = self.replace_state(state);
+                self.redo.push(state);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Repeats the last change that was undone. Returns false if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(state) => {
+                let state = self.replace_state(state);
+                self.undo.push(state);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Keeps the filters and rows before a change, so it can be undone.
+    fn remember(&mut self) {
+        self.undo.push((self.boards.clone(), self.rows.clone()));
+        self.redo.clear();
+    }
+
+    // Sets the filters and rows, returns the previous ones.
+    fn replace_state(
+        &mut self,
+        (boards, rows): (Vec<Filter>, Vec<String>),
+    ) -> (Vec<Filter>, Vec<String>) {
+        (
+            std::mem::replace(&mut self.boards, boards),
+            std::mem::replace(&mut self.rows, rows),
+        )
+    }
+
+    /// Selects the next (or previous) board as the active board.
+    pub fn select_next(&mut self, forward: bool) {
+        let count = self.boards.len();
+        self.active = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        feedback::parse_row,
+        filter::{Constraint, LetterCount},
+    };
+
+    #[test]
+    fn undo_skips_filters_typed_again() {
+        let mut puzzle = Puzzle::new(1, 5);
+        puzzle.change_filter(|filter| filter.add_must_not_occur('x'));
+        puzzle.change_filter(|filter| filter.add_must_not_occur('z'));
+        // typed again, nothing changes
+        puzzle.change_filter(|filter| filter.add_must_not_occur('z'));
+        assert!(puzzle.undo());
+        assert_eq!(puzzle.boards[0].constraints().len(), 1);
+        assert!(puzzle.undo());
+        assert!(puzzle.is_empty());
+        assert!(!puzzle.undo());
+    }
+
+    #[test]
+    fn replace_character_of_count_in_one_step() {
+        let mut puzzle = Puzzle::new(1, 5);
+        let (guess, tiles) = parse_row("speed bbyby", 5).unwrap();
+        puzzle.apply_feedback(&guess, &tiles).unwrap();
+        let exactly_one_e = Constraint::Count(
+            'e',
+            LetterCount {
+                min: 1,
+                max: Some(1),
+            },
+        );
+        assert!(puzzle.boards[0].constraints().contains(&exactly_one_e));
+        puzzle.change_filter(|filter| filter.replace_char(&exactly_one_e, 'a'));
+        let constraints = puzzle.boards[0].constraints();
+        assert!(!constraints.contains(&exactly_one_e));
+        assert!(constraints.contains(&Constraint::Count(
+            'a',
+            LetterCount {
+                min: 1,
+                max: Some(1)
+            }
+        )));
+        assert!(puzzle.undo());
+        assert!(puzzle.boards[0].constraints().contains(&exactly_one_e));
+    }
+
+    #[test]
+    fn replace_character_of_count_with_counted_one() {
+        let mut puzzle = Puzzle::new(1, 5);
+        // at least one e, no c, r, a or n
+        let (guess, tiles) = parse_row("crane bbbby", 5).unwrap();
+        puzzle.apply_feedback(&guess, &tiles).unwrap();
+        let count = |min, max| LetterCount { min, max };
+        let at_most_two_s = Constraint::Count('s', count(0, Some(2)));
+        puzzle.change_filter(|filter| filter.insert(at_most_two_s));
+        puzzle.change_filter(|filter| filter.replace_char(&at_most_two_s, 'e'));
+        // both counts still hold, instead of the one of 's' overwriting the one of 'e'
+        let constraints = puzzle.boards[0].constraints();
+        assert!(constraints.contains(&Constraint::Count('e', count(1, Some(2)))));
+        assert!(!constraints
+            .iter()
+            .any(|c| matches!(c, Constraint::Count('s', _))));
+
+        let no_c = Constraint::Count('c', count(0, Some(0)));
+        puzzle.change_filter(|filter| filter.replace_char(&no_c, 'n'));
+        let constraints = puzzle.boards[0].constraints();
+        assert!(constraints.contains(&Constraint::Count('n', count(0, Some(0)))));
+        assert!(!constraints.contains(&no_c));
+        assert!(puzzle.undo());
+        assert!(puzzle.boards[0].constraints().contains(&no_c));
+    }
+
+    #[test]
+    fn undo_and_redo_a_row() {
+        let mut puzzle = Puzzle::new(2, 5);
+        let (guess, tiles) = parse_row("crane bygbb gggbb", 5).unwrap();
+        puzzle.apply_feedback(&guess, &tiles).unwrap();
+        let constraints = puzzle.boards[1].constraints();
+        assert!(puzzle.undo());
+        assert!(puzzle.is_empty() && puzzle.rows.is_empty());
+        assert!(puzzle.redo());
+        assert_eq!(puzzle.rows, ["crane bygbb gggbb"]);
+        assert_eq!(puzzle.boards[1].constraints(), constraints);
+        assert!(!puzzle.redo());
+    }
+}
@@ -0,0 +1,349 @@
+//! Ranking of guesses by how well they narrow down the remaining candidates.
+
+use crate::{
+    feedback::{pattern, Pattern},
+    words::WordList,
+};
+use anyhow::{Context, Result};
+
+/// The chance of a rare word being the answer, relative to a frequent word.
+const RARE_WORD_WEIGHT: f64 = 0.05;
+
+/// How the suggested words are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Frequent words first, otherwise in the order of the word list.
+    FileOrder,
+    /// Highest expected information of the feedback first.
+    Entropy,
+    /// Smallest number of candidates left in the worst case first.
+    Minimax,
+    /// Smallest expected number of candidates left first, with rare words being unlikely answers.
+    Frequency,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::FileOrder,
+        Strategy::Entropy,
+        Strategy::Minimax,
+        Strategy::Frequency,
+    ];
+
+    /// Parses the short name of a strategy, as used on the command line.
+    pub fn parse(name: &str) -> Result<Strategy> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.short_name() == name)
+            .with_context(|| {
+                let names: Vec<&str> = Strategy::ALL.iter().map(|s| s.short_name()).collect();
+                format!(
+                    "unknown strategy '{}', must be one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Strategy::FileOrder => "file-order",
+            Strategy::Entropy => "entropy",
+            Strategy::Minimax => "minimax",
+            Strategy::Frequency => "frequency",
+        }
+    }
+
+    /// The strategy to switch to when cycling through all strategies.
+    pub fn next(self) -> Strategy {
+        match self {
+            Strategy::FileOrder => Strategy::Entropy,
+            Strategy::Entropy => Strategy::Minimax,
+            Strategy::Minimax => Strategy::Frequency,
+            Strategy::Frequency => Strategy::FileOrder,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::FileOrder => "word list order",
+            Strategy::Entropy => "expected information",
+            Strategy::Minimax => "worst case",
+            Strategy::Frequency => "expected matches left",
+        }
+    }
+
+    /// Formats a score computed by `rank_boards` for display, if the strategy computes one.
+    pub fn format_score(self, score: f64) -> Option<String> {
+        match self {
+            Strategy::FileOrder => None,
+            Strategy::Entropy => Some(format!("{:.2} bits", score)),
+            Strategy::Minimax => Some(format!("at most {} left", score)),
+            Strategy::Frequency => Some(format!("{:.1} left", score)),
+        }
+    }
+}
+
+/// A word which could still be the answer.
+#[derive(Debug, Clone, Copy)]
+pub struct Candidate {
+    /// Index into the answers of the word list.
+    pub index: usize,
+    /// How likely the word is the answer, relative to the other candidates.
+    pub weight: f64,
+}
+
+impl Candidate {
+    pub fn new(index: usize, frequent: bool) -> Candidate {
+        Candidate {
+            index,
+            weight: if frequent { 1.0 } else { RARE_WORD_WEIGHT },
+        }
+    }
+}
+
+/// A guess and its score.
+#[derive(Debug, Clone, Copy)]
+pub struct Suggestion {
+    /// Index into the list of guesses passed to `rank_boards`.
+    pub index: usize,
+    pub score: f64,
+}
+
+/// Ranks all guesses against the candidates of several boards, which could still be the answer,
+/// best guess first.
+///
+/// The guesses are indices into the guesses of the word list. The score of a guess is the sum of
+/// its scores on all boards, guesses with the same score keep their order.
+pub fn rank_boards(
+    strategy: Strategy,
+    words: &WordList,
+    guesses: &[usize],
+    boards: &[&[Candidate]],
+) -> Vec<Suggestion> {
+    let mut patterns = vec![];
+    let mut suggestions: Vec<Suggestion> = guesses
+        .iter()
+        .enumerate()
+        .map(|(index, &guess)| Suggestion {
+            index,
+            score: boards
+                .iter()
+                .map(|candidates| score(strategy, words, guess, candidates, &mut patterns))
+                .sum(),
+        })
+        .collect();
+    match strategy {
+        Strategy::FileOrder | Strategy::Entropy => {
+            suggestions.sort_by(|a, b| b.score.total_cmp(&a.score))
+        }
+        Strategy::Minimax | Strategy::Frequency => {
+            suggestions.sort_by(|a, b| a.score.total_cmp(&b.score))
+        }
+    }
+    suggestions
+}
+
+fn score(
+    strategy: Strategy,
+    words: &WordList,
+    guess: usize,
+    candidates: &[Candidate],
+    patterns: &mut Vec<(Pattern, f64)>,
+) -> f64 {
+    match strategy {
+        Strategy::FileOrder => 0.0,
+        Strategy::Entropy => entropy(
+            &buckets(words, guess, candidates, patterns),
+            candidates.len(),
+        ),
+        Strategy::Minimax => buckets(words, guess, candidates, patterns)
+            .iter()
+            .map(|bucket| bucket.size)
+            .max()
+            .unwrap_or(0) as f64,
+        Strategy::Frequency => {
+            let total_weight: f64 = candidates.iter().map(|c| c.weight).sum();
+            expected_remaining(&buckets(words, guess, candidates, patterns), total_weight)
+        }
+    }
+}
+
+// The candidates giving the same feedback pattern for a guess.
+struct Bucket {
+    size: usize,
+    weight: f64,
+    // the guess is the answer
+    solved: bool,
+}
+
+// Groups the candidates by the feedback pattern they produce for the guess.
+fn buckets(
+    words: &WordList,
+    guess: usize,
+    candidates: &[Candidate],
+    patterns: &mut Vec<(Pattern, f64)>,
+) -> Vec<Bucket> {
+    let guess_word = &words.guesses[guess].0;
+    let solved = pattern(guess_word, guess_word);
+    patterns.clear();
+    patterns.extend(
+        candidates
+            .iter()
+            .map(|candidate| (words.pattern(guess, candidate.index), candidate.weight)),
+    );
+    patterns.sort_unstable_by_key(|(pattern, _)| *pattern);
+    patterns
+        .chunk_by(|a, b| a.0 == b.0)
+        .map(|bucket| Bucket {
+            size: bucket.len(),
+            weight: bucket.iter().map(|(_, weight)| weight).sum(),
+            solved: bucket[0].0 == solved,
+        })
+        .collect()
+}
+
+// The expected information in bits of learning which bucket the answer is in.
+fn entropy(buckets: &[Bucket], total: usize) -> f64 {
+    buckets
+        .iter()
+        .map(|bucket| {
+            let p = bucket.size as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// The expected number of candidates left after the guess, with each bucket
+// weighted by the chance that it contains the answer.
+fn expected_remaining(buckets: &[Bucket], total_weight: f64) -> f64 {
+    buckets
+        .iter()
+        .filter(|bucket| !bucket.solved)
+        .map(|bucket| bucket.weight / total_weight * bucket.size as f64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The answers differ in the first character only, 'pzkfx' tells them all apart.
+    const ANSWERS: [&str; 4] = ["hills", "pills", "kills", "fills"];
+
+    fn words() -> WordList {
+        WordList::of(&ANSWERS[..3], &["fills"], &["pzkfx", "hzzzz", "fzzzz"])
+    }
+
+    fn index(words: &WordList, word: &str) -> usize {
+        words.guesses.iter().position(|w| w.0 == word).unwrap()
+    }
+
+    fn candidates(words: &WordList, answers: &[&str]) -> Vec<Candidate> {
+        answers
+            .iter()
+            .map(|&answer| {
+                let i = index(words, answer);
+                Candidate::new(i, words.answers[i].1)
+            })
+            .collect()
+    }
+
+    // Ranks the guesses against the answers of each board and checks the order and the scores.
+    fn assert_ranked(
+        strategy: Strategy,
+        guesses: &[&str],
+        boards: &[&[&str]],
+        expected: &[(&str, f64)],
+    ) {
+        let words = words();
+        let indices: Vec<usize> = guesses.iter().map(|guess| index(&words, guess)).collect();
+        let candidates: Vec<Vec<Candidate>> = boards
+            .iter()
+            .map(|answers| candidates(&words, answers))
+            .collect();
+        let candidates: Vec<&[Candidate]> = candidates.iter().map(|c| c.as_slice()).collect();
+        let ranked = rank_boards(strategy, &words, &indices, &candidates);
+        let ranked: Vec<(&str, f64)> = ranked.iter().map(|s| (guesses[s.index], s.score)).collect();
+        assert_eq!(ranked.len(), expected.len());
+        for (&(word, score), &(expected_word, expected_score)) in ranked.iter().zip(expected) {
+            assert_eq!(word, expected_word, "{:?}", ranked);
+            assert!(
+                (score - expected_score).abs() < 1e-6,
+                "'{}' scored {} instead of {}",
+                word,
+                score,
+                expected_score
+            );
+        }
+    }
+
+    #[test]
+    fn entropy_of_the_groups() {
+        // 'hills' leaves groups of 1 and 3: -1/4 log2(1/4) - 3/4 log2(3/4)
+        assert_ranked(
+            Strategy::Entropy,
+            &["hills", "pzkfx"],
+            &[&ANSWERS],
+            &[("pzkfx", 2.0), ("hills", 0.811278)],
+        );
+    }
+
+    #[test]
+    fn minimax_of_the_largest_group() {
+        assert_ranked(
+            Strategy::Minimax,
+            &["hills", "pzkfx"],
+            &[&ANSWERS],
+            &[("pzkfx", 1.0), ("hills", 3.0)],
+        );
+    }
+
+    #[test]
+    fn expected_matches_left_without_the_solved_group() {
+        // 'hills' solves the puzzle or leaves 'pills' and the rare 'fills': 1.05 / 2.05 * 2,
+        // while 'hzzzz' may leave 'hills' as well: (1 + 1.05 * 2) / 2.05
+        let answers = ["hills", "pills", "fills"];
+        assert_ranked(
+            Strategy::Frequency,
+            &["hzzzz", "hills"],
+            &[&answers],
+            &[("hills", 1.024390), ("hzzzz", 1.512195)],
+        );
+    }
+
+    #[test]
+    fn rare_words_count_less() {
+        // both guesses split off a single word, 'fzzzz' the rare one: (0.05 + 2 * 2) / 2.05
+        let answers = ["hills", "pills", "fills"];
+        assert_ranked(
+            Strategy::Frequency,
+            &["fzzzz", "hzzzz"],
+            &[&answers],
+            &[("hzzzz", 1.512195), ("fzzzz", 1.975610)],
+        );
+        // the groups have the same sizes, so the order is kept
+        assert_ranked(
+            Strategy::Entropy,
+            &["fzzzz", "hzzzz"],
+            &[&answers],
+            &[("fzzzz", 0.918296), ("hzzzz", 0.918296)],
+        );
+    }
+
+    #[test]
+    fn scores_of_all_boards_are_added() {
+        assert_ranked(
+            Strategy::Entropy,
+            &["hills", "pzkfx"],
+            &[&["hills", "pills"], &["kills", "fills"]],
+            &[("pzkfx", 2.0), ("hills", 1.0)],
+        );
+        assert_ranked(
+            Strategy::FileOrder,
+            &["hills", "pzkfx"],
+            &[&["hills", "pills"], &["kills", "fills"]],
+            &[("hills", 0.0), ("pzkfx", 0.0)],
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Saving the puzzle being solved to a file, to resume it after the app was closed.
+//!
+//! The file is plain text, one setting or filter per line, e.g.
+//!
+//! ```text
+//! answers words.txt
+//! length 5
+//! strategy entropy
+//! row crane bygbb
+//! board
+//! must-not-be 1 c
+//! count a 0 0
+//! ```
+
+use crate::{
+    args::{Args, WORD_LENGTHS},
+    filter::{Constraint, Filter, LetterCount},
+    puzzle::Puzzle,
+    rank::Strategy,
+    read_line, Options, PastAnswers,
+};
+use anyhow::{bail, Context, Result};
+use std::{fs, path::Path, path::PathBuf};
+
+/// A saved puzzle, with the word list and the options it was solved with.
+pub struct Session {
+    pub answers: PathBuf,
+    pub guesses: Option<PathBuf>,
+    pub past: Option<PathBuf>,
+    pub word_length: usize,
+    pub fold_accents: bool,
+    pub puzzle: Puzzle,
+    pub strategy: Strategy,
+    pub hard_mode: bool,
+    pub past_answers: PastAnswers,
+}
+
+impl Session {
+    /// Reads a saved session.
+    pub fn load(filename: &Path) -> Result<Session> {
+        let text = fs::read_to_string(filename)
+            .with_context(|| format!("failed to read {}", filename.display()))?;
+        let mut session = Session {
+            answers: PathBuf::from("words.txt"),
+            guesses: None,
+            past: None,
+            word_length: 5,
+            fold_accents: false,
+            puzzle: Puzzle::new(1, 5),
+            strategy: Strategy::FileOrder,
+            hard_mode: false,
+            past_answers: PastAnswers::Hide,
+        };
+        let mut boards: Vec<Filter> = vec![];
+        let mut rows = vec![];
+        let mut active = 0;
+        for (i, line) in text.lines().enumerate() {
+            session
+                .parse_line(line.trim(), &mut boards, &mut rows, &mut active)
+                .with_context(|| format!("{} line {}: '{}'", filename.display(), i + 1, line))?;
+        }
+        if boards.is_empty() {
+            bail!("{} contains no board", filename.display());
+        }
+        session.puzzle = Puzzle::new(boards.len(), session.word_length);
+        session.puzzle.boards = boards;
+        session.puzzle.rows = rows;
+        session.puzzle.active = active.min(session.puzzle.boards.len() - 1);
+        Ok(session)
+    }
+
+    fn parse_line(
+        &mut self,
+        line: &str,
+        boards: &mut Vec<Filter>,
+        rows: &mut Vec<String>,
+        active: &mut usize,
+    ) -> Result<()> {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let values: Vec<&str> = value.split_whitespace().collect();
+        if let Some(constraint) = parse_constraint(key, &values)? {
+            let board = boards.last_mut().context("filter before the first board")?;
+            // positions are checked against the board, so `insert` cannot fail
+            match constraint {
+                Constraint::MustBe(pos, _) | Constraint::MustNotBe(pos, _)
+                    if pos >= board.positional.len() =>
+                {
+                    bail!("invalid position {}", pos + 1)
+                }
+                _ => board.insert(constraint),
+            }
+            return Ok(());
+        }
+        match (key, &values[..]) {
+            ("" | "#", _) => {}
+            ("answers", _) => self.answers = value.into(),
+            ("guesses", _) => self.guesses = Some(value.into()),
+            ("past", _) => self.past = Some(value.into()),
+            ("length", _) if !boards.is_empty() => {
+                bail!("the word length must be set before the first board")
+            }
+            ("length", [length]) => match length.parse() {
+                Ok(length) if WORD_LENGTHS.contains(&length) => self.word_length = length,
+                _ => bail!("invalid word length"),
+            },
+            ("fold-accents", []) => self.fold_accents = true,
+            ("strategy", [strategy]) => self.strategy = Strategy::parse(strategy)?,
+            ("hard-mode", []) => self.hard_mode = true,
+            ("past-answers", [name]) => self.past_answers = parse_past_answers(name)?,
+            ("row", _) => rows.push(value.to_string()),
+            ("active", [board]) => *active = board.parse()?,
+            ("board", []) => boards.push(Filter::new(self.word_length)),
+            _ => bail!("unknown setting"),
+        }
+        Ok(())
+    }
+}
+
+// A constraint of the filter of a board, `None` for other settings.
+fn parse_constraint(key: &str, values: &[&str]) -> Result<Option<Constraint>> {
+    let constraint = match (key, values) {
+        ("must-be", [pos, ch]) => Constraint::MustBe(position(pos)?, character(ch)?),
+        ("must-not-be", [pos, ch]) => Constraint::MustNotBe(position(pos)?, character(ch)?),
+        ("count", [ch, min, max]) => {
+            let count = LetterCount {
+                min: min.parse()?,
+                max: match *max {
+                    "-" => None,
+                    max => Some(max.parse()?),
+                },
+            };
+            Constraint::Count(character(ch)?, count)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(constraint))
+}
+
+// A position as saved, counting from 1.
+fn position(pos: &str) -> Result<usize> {
+    match pos.parse::<usize>() {
+        Ok(pos) if pos > 0 => Ok(pos - 1),
+        _ => bail!("invalid position '{}'", pos),
+    }
+}
+
+/// Saves the puzzle, the word list and the options.
+pub fn save(filename: &Path, args: &Args, puzzle: &Puzzle, options: &Options) -> Result<()> {
+    let mut lines = vec!["# wordle session, saved after every change".to_string()];
+    lines.push(format!("answers {}", args.answers.display()));
+    if let Some(guesses) = &args.guesses {
+        lines.push(format!("guesses {}", guesses.display()));
+    }
+    if let Some(past) = &args.past {
+        lines.push(format!("past {}", past.display()));
+    }
+    lines.push(format!("length {}", puzzle.word_length()));
+    if options.fold_accents {
+        lines.push("fold-accents".to_string());
+    }
+    lines.push(format!("strategy {}", options.strategy.short_name()));
+    if options.hard_mode {
+        lines.push("hard-mode".to_string());
+    }
+    lines.push(format!(
+        "past-answers {}",
+        past_answers_name(options.past_answers)
+    ));
+    lines.extend(puzzle.rows.iter().map(|row| format!("row {}", row)));
+    lines.push(format!("active {}", puzzle.active));
+    for filter in &puzzle.boards {
+        lines.push("board".to_string());
+        lines.extend(filter.constraints().iter().map(|c| match *c {
+            Constraint::MustBe(pos, ch) => format!("must-be {} {}", pos + 1, ch),
+            Constraint::MustNotBe(pos, ch) => format!("must-not-be {} {}", pos + 1, ch),
+            Constraint::Count(ch, count) => match count.max {
+                Some(max) => format!("count {} {} {}", ch, count.min, max),
+                None => format!("count {} {} -", ch, count.min),
+            },
+        }));
+    }
+    lines.push(String::new());
+    fs::write(filename, lines.join("\n"))
+        .with_context(|| format!("failed to write {}", filename.display()))
+}
+
+/// Asks whether to resume the saved session, if there is one with an unsolved puzzle.
+pub fn offer_resume(filename: &Path) -> Option<Session> {
+    if !filename.exists() {
+        return None;
+    }
+    let session = match Session::load(filename) {
+        Ok(session) => session,
+        Err(e) => {
+            println!("The last session cannot be resumed: {:#}", e);
+            return None;
+        }
+    };
+    if session.puzzle.is_empty() || session.puzzle.unsolved().is_empty() {
+        return None;
+    }
+    if session.puzzle.rows.is_empty() {
+        println!("Resume the last session? (y/n)");
+    } else {
+        println!(
+            "Resume the last session after {}? (y/n)",
+            session.puzzle.rows.join(", ")
+        );
+    }
+    let answer = read_line().trim().to_lowercase();
+    (answer == "y" || answer == "yes").then_some(session)
+}
+
+fn character(ch: &str) -> Result<char> {
+    let mut chars = ch.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => bail!("invalid character '{}'", ch),
+    }
+}
+
+fn past_answers_name(past_answers: PastAnswers) -> &'static str {
+    match past_answers {
+        PastAnswers::Hide => "hide",
+        PastAnswers::Demote => "demote",
+        PastAnswers::Show => "show",
+    }
+}
+
+fn parse_past_answers(name: &str) -> Result<PastAnswers> {
+    match name {
+        "hide" => Ok(PastAnswers::Hide),
+        "demote" => Ok(PastAnswers::Demote),
+        "show" => Ok(PastAnswers::Show),
+        _ => bail!("invalid value '{}' for past answers", name),
+    }
+}
+
+/// A file in the temporary directory, removed when dropped, for tests.
+#[cfg(test)]
+pub struct TempFile(pub PathBuf);
+
+#[cfg(test)]
+impl TempFile {
+    pub fn new(name: &str, text: &str) -> TempFile {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        TempFile(path)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        _ = fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::parse_row;
+
+    #[test]
+    fn save_and_load() {
+        let args = [
+            "--answers",
+            "answers.txt",
+            "--guesses",
+            "guesses.txt",
+            "--past",
+            "past.txt",
+            "--length",
+            "6",
+            "--fold-accents",
+        ];
+        let args = Args::parse(args.map(String::from)).unwrap();
+        let options = Options {
+            strategy: Strategy::Entropy,
+            hard_mode: true,
+            past_answers: PastAnswers::Demote,
+            fold_accents: true,
+        };
+        let mut puzzle = Puzzle::new(3, 6);
+        for row in ["planet bygbbb gggbbb bbbbbg", "ermite bbbbbb ggggyb bbybbb"] {
+            let (guess, tiles) = parse_row(row, 6).unwrap();
+            puzzle.apply_feedback(&guess, &tiles).unwrap();
+        }
+        puzzle.active = 2;
+        puzzle.change_filter(|filter| {
+            filter.insert(Constraint::Count('x', LetterCount { min: 2, max: None }));
+            filter.insert(Constraint::MustNotBe(5, 'q'));
+        });
+        let file = TempFile::new("session-round-trip.txt", "");
+        save(&file.0, &args, &puzzle, &options).unwrap();
+
+        let session = Session::load(&file.0).unwrap();
+        assert_eq!(session.answers, args.answers);
+        assert_eq!(session.guesses, args.guesses);
+        assert_eq!(session.past, args.past);
+        assert_eq!(session.word_length, 6);
+        assert!(session.fold_accents);
+        assert_eq!(session.strategy, Strategy::Entropy);
+        assert!(session.hard_mode);
+        assert_eq!(session.past_answers, PastAnswers::Demote);
+        assert_eq!(session.puzzle.rows, puzzle.rows);
+        assert_eq!(session.puzzle.active, 2);
+        assert_eq!(session.puzzle.boards.len(), 3);
+        for (loaded, saved) in session.puzzle.boards.iter().zip(&puzzle.boards) {
+            assert_eq!(loaded.constraints(), saved.constraints());
+        }
+        let text = fs::read_to_string(&file.0).unwrap();
+        assert!(text.contains("\ncount x 2 -\n"));
+        assert!(text.contains("\nmust-not-be 6 q\n"));
+    }
+
+    #[test]
+    fn invalid_sessions() {
+        for text in [
+            // the positions of a board cannot change
+            "length 5\nboard\nlength 6\nmust-be 6 a",
+            "board\nmust-be 6 a",
+            "board\nmust-not-be 0 a",
+            "must-be 1 a\nboard",
+            "board\ncount a 1",
+            "board\nmust-be 1 ab",
+            "length 12\nboard",
+            "strategy fastest\nboard",
+            "answers words.txt",
+        ] {
+            let file = TempFile::new("session-invalid.txt", text);
+            assert!(Session::load(&file.0).is_err(), "{:?} was loaded", text);
+        }
+    }
+}
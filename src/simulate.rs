@@ -0,0 +1,319 @@
+//! Playing a strategy against many answers, to see how well it does.
+
+use crate::{
+    feedback::{pattern, tiles, Pattern},
+    filter::Filter,
+    play::MAX_GUESSES,
+    rank::Strategy,
+    solver::best_guess,
+    words::{normalize, WordList},
+};
+use anyhow::{bail, Result};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// A game is given up after this many guesses, in case a strategy gets stuck.
+pub const MAX_TURNS: usize = 20;
+
+/// Plays games with a fixed strategy.
+pub struct Simulation<'a> {
+    words: &'a WordList,
+    word_length: usize,
+    strategy: Strategy,
+    hard_mode: bool,
+    /// Guesses played first, regardless of the strategy.
+    openers: Vec<String>,
+    /// The guess of the strategy after the guesses and feedback so far. As the strategy always
+    /// picks the same guess for the same feedback, many games share the same guesses.
+    known_guesses: HashMap<Vec<Pattern>, String>,
+}
+
+/// The outcome of a single game.
+pub struct Game {
+    pub answer: String,
+    /// The number of guesses needed, `None` if the strategy got stuck.
+    pub guesses: Option<usize>,
+    pub duration: Duration,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(
+        words: &'a WordList,
+        word_length: usize,
+        strategy: Strategy,
+        hard_mode: bool,
+        openers: Vec<String>,
+    ) -> Simulation<'a> {
+        Simulation {
+            words,
+            word_length,
+            strategy,
+            hard_mode,
+            openers,
+            known_guesses: HashMap::new(),
+        }
+    }
+
+    /// Plays a game against the answer.
+    pub fn play(&mut self, answer: &str) -> Game {
+        let start = Instant::now();
+        let mut filter = Filter::new(self.word_length);
+        let mut history: Vec<Pattern> = vec![];
+        let mut guesses = None;
+        for turn in 1..=MAX_TURNS {
+            let Some(guess) = self.next_guess(&filter, &history) else {
+                break;
+            };
+            let pattern = pattern(&guess, answer);
+            if guess == answer {
+                guesses = Some(turn);
+                break;
+            }
+            filter.apply_feedback(&guess, &tiles(pattern, self.word_length));
+            history.push(pattern);
+        }
+        Game {
+            answer: answer.to_string(),
+            guesses,
+            duration: start.elapsed(),
+        }
+    }
+
+    // The guess after the feedback so far.
+    fn next_guess(&mut self, filter: &Filter, history: &[Pattern]) -> Option<String> {
+        if let Some(opener) = self.openers.get(history.len()) {
+            return Some(opener.clone());
+        }
+        if let Some(guess) = self.known_guesses.get(history) {
+            return Some(guess.clone());
+        }
+        let guess = best_guess(self.words, filter, self.strategy, self.hard_mode)?.to_string();
+        self.known_guesses.insert(history.to_vec(), guess.clone());
+        Some(guess)
+    }
+}
+
+/// The games played against each answer.
+pub struct Report {
+    pub games: Vec<Game>,
+}
+
+impl Report {
+    /// Plays the simulation against all answers, printing the progress.
+    pub fn run(simulation: &mut Simulation, answers: &[&str]) -> Report {
+        let mut games = Vec::with_capacity(answers.len());
+        for (i, answer) in answers.iter().enumerate() {
+            games.push(simulation.play(answer));
+            if (i + 1) % 100 == 0 {
+                eprintln!("{} of {} answers played", i + 1, answers.len());
+            }
+        }
+        Report { games }
+    }
+
+    /// The average number of guesses of the games which were solved.
+    pub fn average(&self) -> f64 {
+        let solved: Vec<usize> = self.games.iter().filter_map(|g| g.guesses).collect();
+        solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64
+    }
+
+    /// The most guesses needed, `None` if the strategy got stuck in a game.
+    pub fn worst(&self) -> Option<usize> {
+        self.games
+            .iter()
+            .map(|g| g.guesses)
+            .try_fold(0, |worst, guesses| Some(worst.max(guesses?)))
+    }
+
+    /// The games needing more than six guesses, or not solved at all.
+    pub fn failures(&self) -> usize {
+        self.games
+            .iter()
+            .filter(|g| g.guesses.is_none_or(|guesses| guesses > MAX_GUESSES))
+            .count()
+    }
+
+    /// The percentage of games needing more than six guesses.
+    pub fn failure_rate(&self) -> f64 {
+        self.failures() as f64 * 100.0 / self.games.len().max(1) as f64
+    }
+
+    pub fn print(&self) {
+        println!("Answers:         {}", self.games.len());
+        println!("Average guesses: {:.3}", self.average());
+        match self.worst() {
+            Some(worst) => println!("Worst case:      {} guesses", worst),
+            None => println!("Worst case:      not solved within {} guesses", MAX_TURNS),
+        }
+        println!(
+            "Failures:        {} ({:.2}%) needed more than {} guesses",
+            self.failures(),
+            self.failure_rate(),
+            MAX_GUESSES
+        );
+        println!("Guesses needed:");
+        let mut histogram: Vec<(Option<usize>, usize)> = vec![];
+        for game in &self.games {
+            match histogram.iter_mut().find(|(g, _)| *g == game.guesses) {
+                Some((_, count)) => *count += 1,
+                None => histogram.push((game.guesses, 1)),
+            }
+        }
+        // unsolved games last
+        histogram.sort_by_key(|&(guesses, _)| guesses.unwrap_or(usize::MAX));
+        let most = histogram.iter().map(|&(_, count)| count).max().unwrap_or(1);
+        for (guesses, count) in histogram {
+            let label = match guesses {
+                Some(guesses) => guesses.to_string(),
+                None => "stuck".to_string(),
+            };
+            println!(
+                "{:>6}: {:>6} {}",
+                label,
+                count,
+                "#".repeat((count * 50).div_ceil(most))
+            );
+        }
+        let mut hardest: Vec<&Game> = self.games.iter().collect();
+        hardest.sort_by_key(|g| std::cmp::Reverse(g.guesses.unwrap_or(usize::MAX)));
+        println!("Hardest answers:");
+        for game in hardest.iter().take(10) {
+            game.print();
+        }
+        // the first games take longest, as their guesses are not known yet
+        let mut slowest = hardest;
+        slowest.sort_by_key(|g| std::cmp::Reverse(g.duration));
+        println!("Slowest answers:");
+        for game in slowest.iter().take(5) {
+            game.print();
+        }
+    }
+}
+
+impl Game {
+    fn print(&self) {
+        let guesses = match self.guesses {
+            Some(guesses) => format!("{} guesses", guesses),
+            None => "not solved".to_string(),
+        };
+        println!(
+            "- {} ({}, {:.1} ms)",
+            self.answer,
+            guesses,
+            self.duration.as_secs_f64() * 1000.0
+        );
+    }
+}
+
+/// Checks that the opening guesses can be guessed.
+pub fn openers(words: &WordList, openers: &[String], fold_accents: bool) -> Result<Vec<String>> {
+    openers
+        .iter()
+        .map(|opener| {
+            let opener = normalize(opener, fold_accents);
+            if !words.guesses.iter().any(|w| w.0 == opener) {
+                bail!("'{}' is not in the word list", opener);
+            }
+            Ok(opener)
+        })
+        .collect()
+}
+
+/// Checks that there are answers to play against, and that the solver can find them.
+pub fn check_answers(words: &WordList, answers: &[&str]) -> Result<()> {
+    if answers.is_empty() {
+        bail!("there are no answers to play against");
+    }
+    if let Some(answer) = answers
+        .iter()
+        .find(|&&answer| !words.answers.iter().any(|w| w.0 == answer))
+    {
+        bail!("'{}' is not in the answer list", answer);
+    }
+    Ok(())
+}
+
+/// Plays the strategy against every answer and prints the results.
+pub fn simulate(
+    words: &WordList,
+    answers: &[&str],
+    word_length: usize,
+    strategy: Strategy,
+    hard_mode: bool,
+    openers: Vec<String>,
+) -> Result<()> {
+    check_answers(words, answers)?;
+    print!(
+        "Playing against {} answers, ranked by {}",
+        answers.len(),
+        strategy.name()
+    );
+    if hard_mode {
+        print!(" in hard mode");
+    }
+    if !openers.is_empty() {
+        print!(", opening with {}", openers.join(", "));
+    }
+    println!();
+    let mut simulation = Simulation::new(words, word_length, strategy, hard_mode, openers);
+    Report::run(&mut simulation, answers).print();
+    Ok(())
+}
+
+/// Plays each strategy, alone and with each set of openers, against the same answers and prints
+/// a table comparing the results.
+pub fn tournament(
+    words: &WordList,
+    answers: &[&str],
+    word_length: usize,
+    strategies: &[Strategy],
+    hard_mode: bool,
+    openers: &[Vec<String>],
+) -> Result<()> {
+    check_answers(words, answers)?;
+    let mut results: Vec<(String, Report)> = vec![];
+    for opener in std::iter::once(&vec![]).chain(openers) {
+        for &strategy in strategies {
+            let mut name = strategy.short_name().to_string();
+            if !opener.is_empty() {
+                name = format!("{} after {}", name, opener.join(","));
+            }
+            eprintln!("Playing {}...", name);
+            let mut simulation =
+                Simulation::new(words, word_length, strategy, hard_mode, opener.clone());
+            results.push((name, Report::run(&mut simulation, answers)));
+        }
+    }
+    println!(
+        "Played against {} answers{}",
+        answers.len(),
+        if hard_mode { " in hard mode" } else { "" }
+    );
+    let width = results
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:width$}  {:>7}  {:>5}  {:>8}",
+        "Strategy", "Average", "Worst", "Failures"
+    );
+    // the best average first
+    results.sort_by(|a, b| a.1.average().total_cmp(&b.1.average()));
+    for (name, report) in &results {
+        let worst = match report.worst() {
+            Some(worst) => worst.to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:width$}  {:>7.3}  {:>5}  {:>7.2}%",
+            name,
+            report.average(),
+            worst,
+            report.failure_rate()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//! Picking the next guess, the same way suggestions are ranked for the user.
+
+use crate::{
+    filter::Filter,
+    index::Query,
+    rank::{rank_boards, Candidate, Strategy, Suggestion},
+    words::WordList,
+};
+
+/// Ranking every word as a probe is only done for a manageable number of matches.
+pub const MAX_MATCHES_FOR_PROBES: usize = 1000;
+
+/// The suggestions listed for the user only score up to this many matches against each other,
+/// more are listed in file order so input stays responsive. Probes are only added for fewer
+/// matches, see `MAX_MATCHES_FOR_PROBES`.
+pub const MAX_MATCHES_FOR_RANKING: usize = 2000;
+
+/// The guesses for the matches of one or more boards, ranked by a strategy.
+pub struct Ranking {
+    /// The strategy the guesses are ranked by: the chosen one, or file order if there were too
+    /// many matches to score them.
+    pub strategy: Strategy,
+    /// The matches of all boards, each only once, followed by the probes. Indices into the
+    /// guesses of the word list.
+    pub guesses: Vec<usize>,
+    /// The number of matches at the start of the guesses.
+    pub match_count: usize,
+    /// Best guess first, indices into `guesses`.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Ranks the matches of the boards against each other, and the probes if there are few enough
+/// matches, by the summed score on all boards.
+///
+/// Each board is its filter and its matches as indices into the answers. With more than
+/// `max_matches` matches, the guesses are kept in file order.
+pub fn rank_matches(
+    words: &WordList,
+    boards: &[(&Filter, &[usize])],
+    strategy: Strategy,
+    hard_mode: bool,
+    max_matches: usize,
+) -> Ranking {
+    // an answer has the same index in the guesses
+    let mut listed = vec![false; words.answers.len()];
+    let mut guesses = vec![];
+    for &i in boards.iter().flat_map(|&(_, matches)| matches) {
+        if !listed[i] {
+            listed[i] = true;
+            guesses.push(i);
+        }
+    }
+    let match_count = guesses.len();
+    let strategy = if match_count > max_matches {
+        Strategy::FileOrder
+    } else {
+        strategy
+    };
+    if strategy != Strategy::FileOrder && (2..=MAX_MATCHES_FOR_PROBES).contains(&match_count) {
+        let filters: Vec<&Filter> = boards.iter().map(|&(filter, _)| filter).collect();
+        guesses.extend(probe_words(words, &filters, hard_mode));
+    }
+    let candidates: Vec<Vec<Candidate>> = boards
+        .iter()
+        .filter(|(_, matches)| !matches.is_empty())
+        .map(|(_, matches)| {
+            matches
+                .iter()
+                .map(|&i| Candidate::new(i, words.answers[i].1))
+                .collect()
+        })
+        .collect();
+    let candidates: Vec<&[Candidate]> = candidates.iter().map(|c| c.as_slice()).collect();
+    let suggestions = rank_boards(strategy, words, &guesses, &candidates);
+    Ranking {
+        strategy,
+        guesses,
+        match_count,
+        suggestions,
+    }
+}
+
+/// The answers matching the filter as indices into the answers, frequent words first.
+pub fn matching_answers(words: &WordList, filter: &Filter) -> Vec<usize> {
+    let query = words.index.query(filter);
+    let (mut matches, rare): (Vec<usize>, Vec<usize>) = (0..words.answers.len())
+        .filter(|&i| query.matches(i))
+        .partition(|&i| words.answers[i].1);
+    matches.extend(rare);
+    matches
+}
+
+/// The answers matching a filter, kept while the filter changes.
+///
+/// As long as constraints are only added, the answers are narrowed down instead of filtering
+/// all answers again.
+#[derive(Default)]
+pub struct Matches {
+    filter: Option<Filter>,
+    answers: Vec<usize>,
+}
+
+impl Matches {
+    /// The answers matching the filter, like `matching_answers`.
+    pub fn update(&mut self, words: &WordList, filter: &Filter) -> &[usize] {
+        match &self.filter {
+            Some(known) if filter.implies(known) => {
+                let query = words.index.query(filter);
+                self.answers.retain(|&i| query.matches(i));
+            }
+            _ => self.answers = matching_answers(words, filter),
+        }
+        self.filter = Some(filter.clone());
+        &self.answers
+    }
+}
+
+/// Words which are no match on any board, but can be guessed to eliminate matches. In hard mode,
+/// these are only words which can be guessed but are never the answer.
+///
+/// Returns indices into the guesses of the word list.
+pub fn probe_words(words: &WordList, filters: &[&Filter], hard_mode: bool) -> Vec<usize> {
+    let queries: Vec<Query> = filters
+        .iter()
+        .map(|filter| words.index.query(filter))
+        .collect();
+    (0..words.guesses.len())
+        .filter(|&i| {
+            let is_answer = i < words.answers.len();
+            let matches = queries.iter().any(|query| query.matches(i));
+            if hard_mode {
+                !is_answer && matches
+            } else {
+                !is_answer || !matches
+            }
+        })
+        .collect()
+}
+
+/// The best guess for the filter: the top suggestion of the strategy.
+///
+/// Unlike the suggestions listed for the user, any number of matches is ranked, as the callers
+/// play many games and keep the guess for each feedback.
+pub fn best_guess<'a>(
+    words: &'a WordList,
+    filter: &Filter,
+    strategy: Strategy,
+    hard_mode: bool,
+) -> Option<&'a str> {
+    let matches = matching_answers(words, filter);
+    let ranking = rank_matches(
+        words,
+        &[(filter, &matches)],
+        strategy,
+        hard_mode,
+        usize::MAX,
+    );
+    ranking
+        .suggestions
+        .first()
+        .map(|suggestion| words.guesses[ranking.guesses[suggestion.index]].0.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{Constraint, LetterCount};
+
+    #[test]
+    fn update_matches_like_matching_answers() {
+        let words = WordList::of(
+            &["speed", "geese", "eerie", "those", "there", "abide"],
+            &["level", "hotel", "sheep", "ebbed", "erase", "fizzy"],
+            &[],
+        );
+        let count = |min, max| LetterCount { min, max };
+        // whether the constraint is added or removed, and if the filter then implies the last one
+        let steps = [
+            (true, Constraint::Count('e', count(1, None)), true),
+            (true, Constraint::MustNotBe(0, 's'), true),
+            (true, Constraint::MustBe(4, 'e'), true),
+            (true, Constraint::Count('e', count(2, Some(2))), true),
+            (true, Constraint::Count('e', count(3, None)), false),
+            (false, Constraint::MustBe(4, 'e'), false),
+            (true, Constraint::MustNotBe(1, 'h'), true),
+            (false, Constraint::MustNotBe(0, 's'), false),
+            (false, Constraint::Count('e', count(3, None)), false),
+        ];
+        let mut filter = Filter::new(5);
+        let mut matches = Matches::default();
+        assert_eq!(
+            matches.update(&words, &filter),
+            matching_answers(&words, &filter)
+        );
+        for (add, constraint, implied) in steps {
+            let last = filter.clone();
+            if add {
+                filter.insert(constraint);
+            } else {
+                filter.remove(&constraint);
+            }
+            assert_eq!(filter.implies(&last), implied, "{:?}", constraint);
+            assert_eq!(
+                matches.update(&words, &filter),
+                matching_answers(&words, &filter),
+                "{:?}",
+                constraint
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! A decision tree: the guess to play after any feedback, to print as a cheat sheet or to compare
+//! between word lists.
+
+use crate::{
+    feedback::{self, pattern, tiles, Pattern},
+    filter::Filter,
+    rank::Strategy,
+    simulate::MAX_TURNS,
+    solver::best_guess,
+    words::WordList,
+};
+use anyhow::{bail, Context, Result};
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+/// The guess to play for some answers still possible, and what to play next.
+pub struct Node {
+    /// The guess to play, `None` if the strategy got stuck.
+    pub guess: Option<String>,
+    /// The answers still possible before the guess.
+    pub answers: Vec<String>,
+    /// The next node for each pattern the guess can get, except all green. Ordered by pattern,
+    /// so trees of different word lists can be compared line by line.
+    pub branches: Vec<(Pattern, Node)>,
+}
+
+/// The file formats a tree can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Dot,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Format> {
+        match name {
+            "json" => Ok(Format::Json),
+            "dot" => Ok(Format::Dot),
+            _ => bail!("unknown format '{}', must be json or dot", name),
+        }
+    }
+}
+
+/// Builds the tree of the strategy and writes it to the file, or to stdout without a file.
+#[allow(clippy::too_many_arguments)]
+pub fn export(
+    words: &WordList,
+    answers: &[&str],
+    word_length: usize,
+    strategy: Strategy,
+    hard_mode: bool,
+    openers: &[String],
+    format: Format,
+    output: Option<&Path>,
+) -> Result<()> {
+    let tree = build(words, answers, word_length, strategy, hard_mode, openers);
+    let guesses = tree.guesses();
+    let solved: Vec<usize> = guesses.iter().flatten().copied().collect();
+    eprintln!(
+        "The tree solves {} of {} answers with {:.3} guesses on average, at most {}",
+        solved.len(),
+        guesses.len(),
+        solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64,
+        solved.iter().max().unwrap_or(&0)
+    );
+    let text = match format {
+        Format::Json => tree.to_json(word_length),
+        Format::Dot => tree.to_dot(word_length),
+    };
+    match output {
+        Some(output) => fs::write(output, text)
+            .with_context(|| format!("failed to write {}", output.display()))?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Builds the tree of the strategy, starting with the opening guesses.
+pub fn build(
+    words: &WordList,
+    answers: &[&str],
+    word_length: usize,
+    strategy: Strategy,
+    hard_mode: bool,
+    openers: &[String],
+) -> Node {
+    let builder = Builder {
+        words,
+        word_length,
+        strategy,
+        hard_mode,
+        openers,
+    };
+    builder.node(&Filter::new(word_length), answers, 0)
+}
+
+struct Builder<'a> {
+    words: &'a WordList,
+    word_length: usize,
+    strategy: Strategy,
+    hard_mode: bool,
+    openers: &'a [String],
+}
+
+impl Builder<'_> {
+    fn node(&self, filter: &Filter, answers: &[&str], depth: usize) -> Node {
+        let guess = match self.openers.get(depth) {
+            Some(opener) => Some(opener.as_str()),
+            None if depth < MAX_TURNS => {
+                best_guess(self.words, filter, self.strategy, self.hard_mode)
+            }
+            None => None,
+        };
+        let mut node = Node {
+            guess: guess.map(|guess| guess.to_string()),
+            answers: answers.iter().map(|answer| answer.to_string()).collect(),
+            branches: vec![],
+        };
+        let Some(guess) = guess else {
+            return node;
+        };
+        let mut groups: BTreeMap<Pattern, Vec<&str>> = BTreeMap::new();
+        for &answer in answers.iter().filter(|&&answer| answer != guess) {
+            groups
+                .entry(pattern(guess, answer))
+                .or_default()
+                .push(answer);
+        }
+        for (pattern, answers) in groups {
+            let mut filter = filter.clone();
+            filter.apply_feedback(guess, &tiles(pattern, self.word_length));
+            node.branches
+                .push((pattern, self.node(&filter, &answers, depth + 1)));
+        }
+        node
+    }
+}
+
+impl Node {
+    /// The number of guesses needed for each answer, `None` if the strategy got stuck.
+    pub fn guesses(&self) -> Vec<Option<usize>> {
+        let mut result = vec![];
+        self.collect_guesses(1, &mut result);
+        result
+    }
+
+    fn collect_guesses(&self, depth: usize, result: &mut Vec<Option<usize>>) {
+        match &self.guess {
+            None => result.extend(self.answers.iter().map(|_| None)),
+            Some(guess) => {
+                if self.answers.contains(guess) {
+                    result.push(Some(depth));
+                }
+                for (_, node) in &self.branches {
+                    node.collect_guesses(depth + 1, result);
+                }
+            }
+        }
+    }
+
+    /// Writes the tree as JSON, with each node on its own lines.
+    ///
+    /// A node looks like `{"guess": "crane", "answers": 3103, "next": {"bbbbb": {...}}}`, nodes
+    /// where the strategy got stuck have no guess but list the answers left in `"words"`.
+    pub fn to_json(&self, word_length: usize) -> String {
+        let mut json = String::new();
+        self.write_json(word_length, 0, &mut json);
+        json.push('\n');
+        json
+    }
+
+    fn write_json(&self, word_length: usize, indent: usize, json: &mut String) {
+        let pad = "  ".repeat(indent + 1);
+        json.push_str("{\n");
+        match &self.guess {
+            Some(guess) => _ = writeln!(json, "{}\"guess\": \"{}\",", pad, escape(guess)),
+            None => _ = writeln!(json, "{}\"guess\": null,", pad),
+        }
+        _ = write!(json, "{}\"answers\": {}", pad, self.answers.len());
+        if self.guess.is_none() {
+            let words: Vec<String> = self
+                .answers
+                .iter()
+                .map(|word| format!("\"{}\"", escape(word)))
+                .collect();
+            _ = write!(json, ",\n{}\"words\": [{}]", pad, words.join(", "));
+        }
+        if !self.branches.is_empty() {
+            _ = write!(json, ",\n{}\"next\": {{", pad);
+            for (i, (pattern, node)) in self.branches.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                _ = write!(
+                    json,
+                    "\n{}  \"{}\": ",
+                    pad,
+                    feedback::format(*pattern, word_length)
+                );
+                node.write_json(word_length, indent + 2, json);
+            }
+            _ = write!(json, "\n{}}}", pad);
+        }
+        _ = write!(json, "\n{}}}", "  ".repeat(indent));
+    }
+
+    /// Writes the tree as a Graphviz graph, with the patterns as edge labels.
+    pub fn to_dot(&self, word_length: usize) -> String {
+        let mut dot = String::from("digraph wordle {\n  node [shape=box];\n");
+        self.write_dot(word_length, &mut 0, &mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Writes the node and all nodes below it, returns the id of the node.
+    fn write_dot(&self, word_length: usize, next_id: &mut usize, dot: &mut String) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let label = match &self.guess {
+            Some(guess) => escape(guess),
+            None => format!("stuck: {}", escape(&self.answers.join(" "))),
+        };
+        _ = writeln!(
+            dot,
+            "  n{} [label=\"{}\\n{}\"];",
+            id,
+            label,
+            self.answers.len()
+        );
+        for (pattern, node) in &self.branches {
+            let child = node.write_dot(word_length, next_id, dot);
+            _ = writeln!(
+                dot,
+                "  n{} -> n{} [label=\"{}\"];",
+                id,
+                child,
+                feedback::format(*pattern, word_length)
+            );
+        }
+        id
+    }
+}
+
+// Escapes quotes and backslashes for JSON and DOT strings.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,251 @@
+//! Loading of the word lists.
+
+use crate::{
+    feedback::{pattern, Pattern},
+    index::WordIndex,
+    matrix::PatternMatrix,
+};
+use anyhow::{bail, Context, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+    path::Path,
+};
+
+/// The words which can be the answer and the words which can be guessed.
+pub struct WordList {
+    /// Possible answers and whether each is a frequent word.
+    pub answers: Vec<(String, bool)>,
+    /// All words which can be guessed, starting with the answers, so an answer has the same
+    /// index in both lists.
+    pub guesses: Vec<(String, bool)>,
+    /// The guesses encoded for fast filtering, with the same indices.
+    pub index: WordIndex,
+    /// Answers of past puzzles.
+    pub past_answers: HashSet<String>,
+    /// The patterns of all guesses for all answers, if they are cached.
+    pub patterns: Option<PatternMatrix>,
+}
+
+impl WordList {
+    /// Reads the answers and optionally a separate list of allowed guesses.
+    ///
+    /// Without a separate guess list, only the answers can be guessed.
+    /// With `fold_accents`, accented characters are replaced by their base character.
+    pub fn load(
+        answers: impl AsRef<Path>,
+        guesses: Option<impl AsRef<Path>>,
+        word_length: usize,
+        fold_accents: bool,
+    ) -> Result<WordList> {
+        let answers = read_words_from_file(answers, word_length, fold_accents)?;
+        let guesses = match guesses {
+            Some(guesses) => read_words_from_file(guesses, word_length, fold_accents)?,
+            None => vec![],
+        };
+        WordList::new(answers, guesses, word_length)
+    }
+
+    /// Creates the word list from the answers and other words which can be guessed.
+    pub fn new(
+        answers: Vec<(String, bool)>,
+        guesses: Vec<(String, bool)>,
+        word_length: usize,
+    ) -> Result<WordList> {
+        let known: HashSet<String> = answers.iter().map(|w| w.0.clone()).collect();
+        let mut all_guesses = answers.clone();
+        all_guesses.extend(guesses.into_iter().filter(|w| !known.contains(&w.0)));
+        let index = WordIndex::new(all_guesses.iter().map(|w| w.0.as_str()), word_length)?;
+        Ok(WordList {
+            answers,
+            guesses: all_guesses,
+            index,
+            past_answers: HashSet::new(),
+            patterns: None,
+        })
+    }
+
+    /// Loads the patterns of all guesses for all answers from a cache file, which is
+    /// rebuilt if it is missing or the word list has changed.
+    pub fn load_patterns(&mut self, filename: impl AsRef<Path>) -> Result<()> {
+        self.patterns = Some(PatternMatrix::load_or_build(filename, self)?);
+        Ok(())
+    }
+
+    /// The pattern of a guess for an answer, by their index in `guesses` and `answers`.
+    pub fn pattern(&self, guess: usize, answer: usize) -> Pattern {
+        match &self.patterns {
+            Some(patterns) => patterns.get(guess, answer),
+            None => pattern(&self.guesses[guess].0, &self.answers[answer].0),
+        }
+    }
+
+    /// The frequent answers, in the order of the word list. These are the words the answer of
+    /// a game is picked from.
+    pub fn frequent_answers(&self) -> Result<Vec<&str>> {
+        let frequent: Vec<&str> = self
+            .answers
+            .iter()
+            .filter(|w| w.1)
+            .map(|w| w.0.as_str())
+            .collect();
+        if frequent.is_empty() {
+            bail!("there are no frequent words to pick the answer from");
+        }
+        Ok(frequent)
+    }
+
+    /// Good words to start with: frequent answers made of distinct, common characters.
+    pub fn start_words(&self, count: usize) -> Vec<&str> {
+        let frequent = self.frequent_answers().unwrap_or_default();
+        // the number of words each character occurs in
+        let mut occurrences: HashMap<char, usize> = HashMap::new();
+        for word in &frequent {
+            let mut chars: Vec<char> = word.chars().collect();
+            chars.sort();
+            chars.dedup();
+            for ch in chars {
+                *occurrences.entry(ch).or_default() += 1;
+            }
+        }
+        let score = |word: &str| {
+            let mut chars: Vec<char> = word.chars().collect();
+            chars.sort();
+            chars.dedup();
+            chars.iter().map(|ch| occurrences[ch]).sum::<usize>()
+        };
+        let mut words = frequent;
+        words.sort_by_key(|&word| std::cmp::Reverse(score(word)));
+        words.truncate(count);
+        words
+    }
+
+    /// Reads the answers of past puzzles.
+    ///
+    /// Each line contains an answer, optionally with other columns like the date or the
+    /// puzzle number, e.g. `2022-02-03 227 those`. The answer is the only column consisting
+    /// of letters only.
+    pub fn load_past_answers(
+        &mut self,
+        filename: impl AsRef<Path>,
+        word_length: usize,
+        fold_accents: bool,
+    ) -> Result<()> {
+        let filename = filename.as_ref();
+        self.past_answers = read_to_string(filename)
+            .with_context(|| format!("failed to read {}", filename.display()))?
+            .lines()
+            .filter_map(|line| {
+                line.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                    .find(|s| {
+                        s.chars().count() == word_length && s.chars().all(|c| c.is_alphabetic())
+                    })
+            })
+            .map(|s| normalize(s, fold_accents))
+            .collect();
+        Ok(())
+    }
+}
+
+/// Reads the words of a file, without telling frequent and rare words apart.
+pub fn read_words(
+    filename: impl AsRef<Path>,
+    word_length: usize,
+    fold_accents: bool,
+) -> Result<Vec<String>> {
+    Ok(read_words_from_file(filename, word_length, fold_accents)?
+        .into_iter()
+        .map(|w| w.0)
+        .collect())
+}
+
+fn read_words_from_file(
+    filename: impl AsRef<Path>,
+    word_length: usize,
+    fold_accents: bool,
+) -> Result<Vec<(String, bool)>> {
+    // The file is expected to contain words with a leading + or -.
+    // A + indicates a frequent word. Words without a leading + or - are considered frequent.
+    let filename = filename.as_ref();
+    let mut words: Vec<(String, bool)> = vec![];
+    // folding accents can turn different words into the same word
+    let mut known: HashMap<String, usize> = HashMap::new();
+    for line in read_to_string(filename)
+        .with_context(|| format!("failed to read {}", filename.display()))?
+        .lines()
+        .map(|s| s.trim())
+    {
+        let (word, frequent) = match line.strip_prefix('-') {
+            Some(word) => (word, false),
+            None => (line.strip_prefix('+').unwrap_or(line), true),
+        };
+        let word = normalize(word, fold_accents);
+        if word.chars().count() != word_length || !word.chars().all(|c| c.is_alphabetic()) {
+            continue;
+        }
+        match known.get(&word) {
+            Some(&i) => words[i].1 |= frequent,
+            None => {
+                known.insert(word.clone(), words.len());
+                words.push((word, frequent));
+            }
+        }
+    }
+    if words.is_empty() {
+        bail!(
+            "{} contains no words of {} characters",
+            filename.display(),
+            word_length
+        );
+    }
+    Ok(words)
+}
+
+/// Converts a word to lower case, optionally replacing accented characters by their base character.
+pub fn normalize(word: &str, fold_accents: bool) -> String {
+    word.to_lowercase()
+        .chars()
+        .map(|ch| if fold_accents { fold_accent(ch) } else { ch })
+        .collect()
+}
+
+/// Replaces an accented latin character by its base character, e.g. 'é' by 'e'.
+///
+/// Characters which are letters of their own, like 'ß' or 'æ', are kept.
+pub fn fold_accent(ch: char) -> char {
+    match ch {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => ch,
+    }
+}
+
+#[cfg(test)]
+impl WordList {
+    /// A word list of 5 characters with frequent and rare answers and other guesses, for tests.
+    pub fn of(frequent: &[&str], rare: &[&str], guesses: &[&str]) -> WordList {
+        let words = |words: &[&str], frequent: bool| -> Vec<(String, bool)> {
+            words.iter().map(|w| (w.to_string(), frequent)).collect()
+        };
+        let mut answers = words(frequent, true);
+        answers.extend(words(rare, false));
+        WordList::new(answers, words(guesses, true), 5).unwrap()
+    }
+}